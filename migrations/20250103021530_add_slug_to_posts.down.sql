@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS post_slug_history;
+ALTER TABLE posts DROP CONSTRAINT IF EXISTS posts_username_slug_key;
+ALTER TABLE posts DROP COLUMN IF EXISTS slug;
@@ -0,0 +1,37 @@
+-- Add up migration script here
+ALTER TABLE posts ADD COLUMN slug TEXT;
+-- Same rule as utils::slugify: lowercase, and every run of characters that
+-- are not letters or digits becomes one '-'. Like slugify this keeps non-ASCII
+-- letters, which [:alnum:] matches in a UTF-8 database.
+UPDATE posts
+SET slug = COALESCE(
+    NULLIF(trim(BOTH '-' FROM regexp_replace(lower(title), '[^[:alnum:]]+', '-', 'g')), ''),
+    'post'
+)
+WHERE slug IS NULL;
+-- Number the posts of one author that slugify to the same title, oldest first.
+WITH numbered AS (
+    SELECT id, row_number() OVER (PARTITION BY username, slug ORDER BY created_at, id) AS n
+    FROM posts
+)
+UPDATE posts SET slug = posts.slug || '-' || numbered.n
+FROM numbered
+WHERE posts.id = numbered.id AND numbered.n > 1;
+-- A numbered slug can still meet a title that slugifies to it, e.g. "a-2".
+WITH numbered AS (
+    SELECT id, row_number() OVER (PARTITION BY username, slug ORDER BY created_at, id) AS n
+    FROM posts
+)
+UPDATE posts SET slug = posts.slug || '-' || posts.id
+FROM numbered
+WHERE posts.id = numbered.id AND numbered.n > 1;
+ALTER TABLE posts ALTER COLUMN slug SET NOT NULL;
+ALTER TABLE posts ADD CONSTRAINT posts_username_slug_key UNIQUE (username, slug);
+
+CREATE TABLE post_slug_history (
+    username TEXT NOT NULL,
+    slug TEXT NOT NULL,
+    post_id TEXT NOT NULL REFERENCES posts (id) ON DELETE CASCADE,
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (username, slug)
+);
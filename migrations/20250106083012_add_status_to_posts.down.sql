@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS posts_username_status_idx;
+ALTER TABLE posts DROP COLUMN IF EXISTS published_at;
+ALTER TABLE posts DROP COLUMN IF EXISTS status;
+DROP TYPE IF EXISTS post_status;
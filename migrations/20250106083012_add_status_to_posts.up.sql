@@ -0,0 +1,10 @@
+-- Add up migration script here
+CREATE TYPE post_status AS ENUM ('draft', 'published', 'archived');
+
+-- posts written before the lifecycle existed were already live
+ALTER TABLE posts ADD COLUMN status post_status NOT NULL DEFAULT 'published';
+ALTER TABLE posts ALTER COLUMN status SET DEFAULT 'draft';
+ALTER TABLE posts ADD COLUMN published_at timestamptz;
+UPDATE posts SET published_at = created_at;
+
+CREATE INDEX posts_username_status_idx ON posts (username, status);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS posts_scheduled_idx;
+ALTER TABLE posts DROP COLUMN IF EXISTS publish_at;
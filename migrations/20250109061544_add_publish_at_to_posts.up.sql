@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE posts ADD COLUMN publish_at timestamptz;
+
+CREATE INDEX posts_scheduled_idx ON posts (publish_at) WHERE status = 'draft' AND publish_at IS NOT NULL;
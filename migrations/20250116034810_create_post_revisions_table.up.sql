@@ -0,0 +1,14 @@
+-- Add up migration script here
+CREATE TABLE post_revisions (
+    id TEXT PRIMARY KEY,
+    post_id TEXT NOT NULL REFERENCES posts (id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at timestamptz DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (post_id, revision)
+);
+
+-- the current version of existing posts becomes their first revision
+INSERT INTO post_revisions (id, post_id, revision, title, content, created_at)
+SELECT id || '-1', id, 1, title, content, updated_at FROM posts;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- NULL until the post is next written; readers render such posts on the fly
+ALTER TABLE posts ADD COLUMN content_html TEXT;
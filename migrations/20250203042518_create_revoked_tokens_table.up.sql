@@ -0,0 +1,10 @@
+-- Add up migration script here
+CREATE TABLE revoked_tokens (
+    jti TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    -- the row is only needed until the token would have expired anyway
+    expires_at timestamptz NOT NULL,
+    revoked_at timestamptz DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE users ADD COLUMN tokens_valid_after timestamptz;
@@ -0,0 +1,8 @@
+-- Add up migration script here
+CREATE TABLE login_attempts (
+    -- "user:<username>" or "ip:<address>"
+    key TEXT PRIMARY KEY,
+    failures INTEGER NOT NULL DEFAULT 0,
+    last_failed_at timestamptz,
+    locked_until timestamptz
+);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DELETE FROM casbin_rule WHERE ptype = 'g' AND v1 LIKE 'role:%';
+DELETE FROM casbin_rule WHERE ptype = 'p' AND v0 LIKE 'role:%';
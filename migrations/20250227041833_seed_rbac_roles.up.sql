@@ -0,0 +1,22 @@
+-- Add up migration script here
+-- Roles inherit along admin -> editor -> author -> reader.
+INSERT INTO casbin_rule (ptype, v0, v1, v2, v3, v4, v5) VALUES
+    ('g', 'role:admin', 'role:editor', '', '', '', ''),
+    ('g', 'role:editor', 'role:author', '', '', '', ''),
+    ('g', 'role:author', 'role:reader', '', '', '', ''),
+    ('p', 'role:reader', '/api/posts', 'GET', '', '', ''),
+    ('p', 'role:reader', '/api/posts/*', 'GET', '', '', ''),
+    ('p', 'role:author', '/api/posts', '(GET)|(POST)|(PUT)|(DELETE)', '', '', ''),
+    ('p', 'role:author', '/api/posts/*', '(GET)|(POST)|(PUT)|(DELETE)', '', '', ''),
+    ('p', 'role:editor', 'posts:any', '(read)|(write)', '', '', ''),
+    ('p', 'role:admin', '/api/users/*', '(GET)|(POST)|(PUT)|(DELETE)', '', '', ''),
+    ('p', 'role:admin', '/api/admin/*', '(GET)|(POST)|(PUT)|(DELETE)', '', '', '')
+ON CONFLICT DO NOTHING;
+
+-- Existing users keep writing their posts, under the subject user_subject
+-- gives them. The first admin is granted by hand:
+--   INSERT INTO casbin_rule (ptype, v0, v1, v2, v3, v4, v5)
+--   VALUES ('g', '<username>', 'role:admin', '', '', '', '');
+INSERT INTO casbin_rule (ptype, v0, v1, v2, v3, v4, v5)
+SELECT 'g', replace(replace(username, '%', '%25'), ':', '%3A'), 'role:author', '', '', '', '' FROM users
+ON CONFLICT DO NOTHING;
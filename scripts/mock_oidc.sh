@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+set -x
+set -eo pipefail
+
+# Runs a mock OpenID Connect provider for local single sign-on testing.
+# Its issuer is http://localhost:${OIDC_PORT}/${OIDC_ISSUER_ID}; any client id
+# and secret are accepted, and the login page lets you pick the `sub` and
+# extra claims (e.g. {"email": "me@example.com", "email_verified": true}).
+OIDC_PORT="${OIDC_PORT:=8080}"
+OIDC_ISSUER_ID="${OIDC_ISSUER_ID:=default}"
+
+# if a mock provider is running, print instructions to kill it and exit
+RUNNING_OIDC_CONTAINER=$(docker ps --filter 'name=mock_oidc' --format '{{.ID}}')
+if [[ -n $RUNNING_OIDC_CONTAINER ]]; then
+  echo >&2 "there is a mock oidc container already running, kill it with"
+  echo >&2 "    docker kill ${RUNNING_OIDC_CONTAINER}"
+  exit 1
+fi
+CONTAINER_NAME="mock_oidc_$(date '+%s')"
+docker run \
+    --env SERVER_PORT=8080 \
+    --publish "${OIDC_PORT}":8080 \
+    --detach \
+    --name "${CONTAINER_NAME}" \
+    ghcr.io/navikt/mock-oauth2-server:2.1.10
+
+until curl --silent --fail \
+  "http://localhost:${OIDC_PORT}/${OIDC_ISSUER_ID}/.well-known/openid-configuration" \
+  > /dev/null; do
+  >&2 echo "Mock provider is still unavailable - sleeping"
+  sleep 1
+done
+
+>&2 echo "Mock provider is up, issuer is http://localhost:${OIDC_PORT}/${OIDC_ISSUER_ID}"
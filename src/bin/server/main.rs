@@ -1,6 +1,9 @@
 use blog_rs::{
-    config::get_config, domain::blog::service::Service, inbound::http::http_server::HttpServer,
-    logger, outbound::db::postgres::Pg,
+    config::get_config,
+    domain::blog::service::Service,
+    inbound::{http::http_server::HttpServer, scheduler::Scheduler},
+    logger,
+    outbound::{db::postgres::Pg, mail::AnyMailer, oidc::OidcClient},
 };
 
 #[tokio::main]
@@ -8,7 +11,11 @@ async fn main() -> anyhow::Result<()> {
     let config = get_config()?;
     logger::init(&config.logger);
     let pg = Pg::new(config.database.clone()).await?;
-    let blog_service = Service::new(pg);
+    let mailer = AnyMailer::new(&config.mail)?;
+    let identity_provider = OidcClient::new(config.auth.oidc.as_ref())?;
+    let blog_service = Service::new(pg, mailer, identity_provider);
+    let scheduler = Scheduler::new(blog_service.clone(), &config);
+    tokio::spawn(scheduler.run());
     let http_server = HttpServer::new(blog_service, config).await?;
     http_server.run().await
 }
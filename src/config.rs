@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use serde_variant::to_variant_name;
 
+use crate::domain::blog::models::roles::Role;
+
 pub fn get_config() -> Result<Settings, config::ConfigError> {
     let base_path = std::env::current_dir().expect("Failed to determine the current directory");
     let config_dir = base_path.join("config");
@@ -56,6 +58,9 @@ pub struct Settings {
     pub auth: AuthSettings,
     pub database: DatabaseSettings,
     pub logger: LoggerSettings,
+    pub mail: MailSettings,
+    pub scheduler: SchedulerSettings,
+    pub trash: TrashSettings,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -66,8 +71,113 @@ pub struct ApplicationSettings {
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct AuthSettings {
+    /// Shared HS512 secret, used when no `keys` are configured.
     pub secret: String,
+    /// Seconds an access token stays valid.
+    pub expiration: u64,
+    /// Seconds a refresh token stays valid.
+    pub refresh_expiration: u64,
+    /// Seconds a password reset token stays valid.
+    pub password_reset_expiration: u64,
+    /// Role given to users when they register or are provisioned by SSO.
+    pub default_role: Role,
+    pub email_verification: EmailVerificationSettings,
+    pub login_throttle: LoginThrottleSettings,
+    pub two_factor: TwoFactorSettings,
+    /// Single sign-on through an OpenID Connect provider; off when unset.
+    #[serde(default)]
+    pub oidc: Option<OidcSettings>,
+    /// `kid` of the key in `keys` that signs new tokens.
+    pub signing_kid: Option<String>,
+    /// Asymmetric keys; every key verifies tokens, so retired keys can stay
+    /// here until the tokens they signed have expired.
+    #[serde(default)]
+    pub keys: Vec<JwtKeySettings>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct EmailVerificationSettings {
+    /// Seconds an email verification token stays valid.
     pub expiration: u64,
+    /// What an account cannot do until its email address is verified.
+    pub require_for: VerificationRequirement,
+}
+
+impl EmailVerificationSettings {
+    /// Whether unverified accounts are refused tokens, by any login method.
+    pub fn required_for_login(&self) -> bool {
+        self.require_for == VerificationRequirement::Login
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationRequirement {
+    Nothing,
+    Login,
+    CreatePost,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LoginThrottleSettings {
+    /// Failed logins for one username before the account is locked.
+    pub max_failures: u32,
+    /// Failed logins from one client IP before the IP is locked.
+    pub ip_max_failures: u32,
+    /// Seconds to wait after the first failure, doubled after each further one.
+    pub backoff_base: u64,
+    /// Upper bound of the wait between two attempts, in seconds.
+    pub backoff_max: u64,
+    /// Seconds an account or IP stays locked.
+    pub lockout_duration: u64,
+    /// Take the client IP from `X-Forwarded-For`; only safe behind a proxy
+    /// that sets it.
+    #[serde(default)]
+    pub trust_forwarded_for: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TwoFactorSettings {
+    /// Name authenticator apps show next to the account.
+    pub issuer: String,
+    /// Seconds a login challenge waits for the second factor.
+    pub challenge_expiration: u64,
+    /// Recovery codes handed out when 2FA is enabled.
+    pub recovery_codes: usize,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct OidcSettings {
+    /// Issuer URL; the provider is discovered from
+    /// `<issuer>/.well-known/openid-configuration`.
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// Must point at `/api/auth/oidc/callback` and be registered with the
+    /// provider.
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    /// Seconds the provider has to send the user back.
+    pub state_expiration: u64,
+    /// Create an account for identities that match no existing user.
+    pub auto_provision: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct JwtKeySettings {
+    pub kid: String,
+    pub algorithm: JwtAlgorithm,
+    /// PKCS#8 PEM (or PKCS#1 for RSA); only needed for the signing key.
+    pub private_key: Option<String>,
+    /// SubjectPublicKeyInfo PEM.
+    pub public_key: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum JwtAlgorithm {
+    RS256,
+    ES256,
+    EdDSA,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -79,6 +189,45 @@ pub struct DatabaseSettings {
     pub database_name: String,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct MailSettings {
+    /// Sender of outgoing emails, e.g. `Blog <no-reply@example.com>`.
+    pub from: String,
+    pub transport: MailTransport,
+    /// Directory the file transport writes emails to.
+    pub file_dir: String,
+    pub smtp: Option<SmtpSettings>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MailTransport {
+    Smtp,
+    File,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Upgrade the connection with STARTTLS.
+    pub starttls: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SchedulerSettings {
+    /// Seconds between two runs of the background jobs.
+    pub interval: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TrashSettings {
+    /// Days a deleted post stays in the trash before it is purged.
+    pub retention_days: u32,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct LoggerSettings {
     pub pretty_backtrace: bool,
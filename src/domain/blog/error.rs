@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use crate::inbound::http::response::ApiError;
+use crate::inbound::http::response::{field_errors, ApiError};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -10,6 +10,14 @@ pub enum Error {
     Custom(String),
     #[error("{0}")]
     Unauthorized(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    TooManyRequests(String),
     #[error(transparent)]
     UtilsError(#[from] crate::utils::error::Error),
     #[error(transparent)]
@@ -19,7 +27,7 @@ pub enum Error {
 impl From<Error> for ApiError {
     fn from(e: Error) -> Self {
         match e {
-            Error::ValidationError(err) => ApiError::UnprocessableEntity(err.to_string()),
+            Error::ValidationError(err) => ApiError::ValidationFailed(field_errors(&err)),
             Error::Unknown(err) => {
                 tracing::error!("{:?}\n{}", err, err.backtrace());
                 ApiError::InternalServerError(err.to_string())
@@ -30,6 +38,10 @@ impl From<Error> for ApiError {
             }
             Error::Custom(err) => ApiError::BadRequestError(err),
             Error::Unauthorized(err) => ApiError::AuthorizationError(err),
+            Error::NotFound(err) => ApiError::NotFound(err),
+            Error::Conflict(err) => ApiError::Conflict(err),
+            Error::Forbidden(err) => ApiError::PermissionDenied(err),
+            Error::TooManyRequests(err) => ApiError::TooManyRequests(err),
         }
     }
 }
@@ -0,0 +1,85 @@
+use std::{borrow::Cow, sync::LazyLock};
+
+use pulldown_cmark::{html, Options, Parser};
+
+/// Prefix of every id in rendered posts, so user content cannot clobber the
+/// ids of the page it is embedded in.
+const ID_PREFIX: &str = "user-content-";
+
+static SANITIZER: LazyLock<ammonia::Builder<'static>> = LazyLock::new(|| {
+    let mut builder = ammonia::Builder::default();
+    builder
+        // task list checkboxes
+        .add_tags(["input"])
+        .add_tag_attributes("input", ["type", "checked", "disabled"])
+        // footnote references and definitions link to each other by id
+        .add_tag_attributes("sup", ["id", "class"])
+        .add_tag_attributes("div", ["id", "class"])
+        .id_prefix(Some(ID_PREFIX))
+        .attribute_filter(|element, attribute, value| match (element, attribute) {
+            ("a", "href") if value.starts_with('#') => {
+                Some(Cow::Owned(format!("#{ID_PREFIX}{}", &value[1..])))
+            }
+            _ => Some(Cow::Borrowed(value)),
+        })
+        // fenced code blocks carry their language
+        .add_tag_attributes("code", ["class"]);
+    builder
+});
+
+/// Renders CommonMark with the GFM tables, footnotes, strikethrough and task
+/// list extensions to HTML, then sanitizes it so it is safe to embed as is.
+pub fn render_markdown(content: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(content, options);
+    let mut unsafe_html = String::with_capacity(content.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, parser);
+    SANITIZER.clean(&unsafe_html).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_and_event_handlers_are_stripped() {
+        let html = render_markdown(
+            "<script>alert(1)</script>\n\n<img src=x onerror=alert(1)> [x](javascript:alert(1))",
+        );
+        assert!(!html.contains("<script"), "{html}");
+        assert!(!html.contains("onerror"), "{html}");
+        assert!(!html.contains("javascript:"), "{html}");
+        assert!(html.contains("<img src=\"x\">"), "{html}");
+    }
+
+    #[test]
+    fn ids_are_prefixed() {
+        let html = render_markdown(r##"<div id="login-form">x</div> <a href="#top">top</a>"##);
+        assert!(html.contains(r#"id="user-content-login-form""#), "{html}");
+        assert!(html.contains(r##"href="#user-content-top""##), "{html}");
+    }
+
+    #[test]
+    fn footnotes_link_to_their_definitions() {
+        let html = render_markdown("Claim[^note].\n\n[^note]: Source.");
+        assert!(
+            html.contains(r##"<a href="#user-content-note" rel="noopener noreferrer">1</a>"##),
+            "{html}"
+        );
+        assert!(
+            html.contains(r#"<div class="footnote-definition" id="user-content-note">"#),
+            "{html}"
+        );
+    }
+
+    #[test]
+    fn tables_survive() {
+        let html = render_markdown("| a | b |\n|---|---|\n| 1 | 2 |");
+        assert!(html.contains("<table>"), "{html}");
+        assert!(html.contains("<th>a</th>"), "{html}");
+        assert!(html.contains("<td>2</td>"), "{html}");
+    }
+}
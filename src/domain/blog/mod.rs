@@ -1,4 +1,5 @@
 pub mod error;
+pub mod markdown;
 pub mod models;
 pub mod ports;
 pub mod service;
@@ -0,0 +1,124 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::domain::blog::error::Error;
+
+/// Prefix telling personal access tokens apart from JWTs in the
+/// `Authorization` header.
+pub const ACCESS_TOKEN_PREFIX: &str = "blog_pat_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TokenScope {
+    #[serde(rename = "posts:read")]
+    PostsRead,
+    #[serde(rename = "posts:write")]
+    PostsWrite,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::PostsRead => "posts:read",
+            TokenScope::PostsWrite => "posts:write",
+        }
+    }
+}
+
+/// A personal access token; only the hash of the secret is kept.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AccessToken {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AccessToken {
+    /// Whether the token grants `scope`; writing posts implies reading them.
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        let granted = |scope: TokenScope| self.scopes.iter().any(|s| s == scope.as_str());
+        match scope {
+            TokenScope::PostsRead => {
+                granted(TokenScope::PostsRead) || granted(TokenScope::PostsWrite)
+            }
+            TokenScope::PostsWrite => granted(TokenScope::PostsWrite),
+        }
+    }
+}
+
+/// A freshly created token together with its secret, which is shown once.
+#[derive(Debug, Clone)]
+pub struct NewAccessToken {
+    pub token: AccessToken,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct CreateAccessTokenRequest {
+    pub user_id: String,
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(min = 1))]
+    pub scopes: Vec<TokenScope>,
+    #[validate(custom(function = "validate_expires_at"))]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl CreateAccessTokenRequest {
+    pub fn new(
+        user_id: String,
+        name: String,
+        mut scopes: Vec<TokenScope>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, Error> {
+        scopes.sort_by_key(|scope| scope.as_str());
+        scopes.dedup();
+        let req = Self {
+            user_id,
+            name: name.trim().to_string(),
+            scopes,
+            expires_at,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct ListAccessTokensRequest {
+    pub user_id: String,
+}
+
+impl ListAccessTokensRequest {
+    pub fn new(user_id: String) -> Result<Self, Error> {
+        let req = Self { user_id };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct RevokeAccessTokenRequest {
+    pub user_id: String,
+    pub id: String,
+}
+
+impl RevokeAccessTokenRequest {
+    pub fn new(user_id: String, id: String) -> Result<Self, Error> {
+        let req = Self { user_id, id };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+fn validate_expires_at(expires_at: &DateTime<Utc>) -> Result<(), ValidationError> {
+    if *expires_at <= Utc::now() {
+        return Err(ValidationError::new("expires_at_in_past"));
+    }
+    Ok(())
+}
@@ -0,0 +1,311 @@
+use chrono::{DateTime, Duration, Utc};
+use validator::Validate;
+
+use crate::{
+    domain::blog::error::Error,
+    utils::{self, jwt::JWT},
+};
+
+/// A short-lived access token together with the opaque refresh token that
+/// can be exchanged for the next pair.
+#[derive(Debug, Clone)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+    /// Seconds until `access_token` expires.
+    pub expires_in: u64,
+}
+
+/// Outcome of a successful password check: either the tokens, or a
+/// challenge to be completed with a second factor.
+#[derive(Debug, Clone)]
+pub enum LoginResponse {
+    Authenticated(TokenPair),
+    TwoFactorRequired {
+        challenge_token: String,
+        /// Seconds until `challenge_token` expires.
+        expires_in: u64,
+    },
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RefreshToken {
+    pub id: String,
+    pub user_id: String,
+    pub family_id: String,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct RefreshTokenRequest {
+    #[validate(length(min = 1))]
+    pub refresh_token: String,
+    pub jwt: JWT,
+    pub expiration: u64,
+    pub refresh_expiration: u64,
+    /// Refuse users whose email address has not been verified yet.
+    pub require_verified_email: bool,
+}
+
+impl RefreshTokenRequest {
+    pub fn new(
+        refresh_token: String,
+        jwt: JWT,
+        expiration: u64,
+        refresh_expiration: u64,
+        require_verified_email: bool,
+    ) -> Result<Self, Error> {
+        let req = Self {
+            refresh_token,
+            jwt,
+            expiration,
+            refresh_expiration,
+            require_verified_email,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct LogoutRequest {
+    #[validate(length(min = 1))]
+    pub jti: String,
+    pub user_id: String,
+    pub expires_at: DateTime<Utc>,
+    /// Refresh token of the same session, whose family is revoked as well.
+    pub refresh_token: Option<String>,
+}
+
+impl LogoutRequest {
+    pub fn new(
+        jti: String,
+        user_id: String,
+        exp: u64,
+        refresh_token: Option<String>,
+    ) -> Result<Self, Error> {
+        let expires_at = DateTime::from_timestamp(exp as i64, 0)
+            .ok_or_else(|| Error::Custom("invalid token expiration".to_string()))?;
+        let req = Self {
+            jti,
+            user_id,
+            expires_at,
+            refresh_token,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct LogoutAllRequest {
+    pub user_id: String,
+}
+
+impl LogoutAllRequest {
+    pub fn new(user_id: String) -> Result<Self, Error> {
+        let req = Self { user_id };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct ChangePasswordRequest {
+    pub user_id: String,
+    pub current_password: String,
+    #[validate(length(min = 8, max = 18))]
+    pub new_password: String,
+}
+
+impl ChangePasswordRequest {
+    pub fn new(
+        user_id: String,
+        current_password: String,
+        new_password: String,
+    ) -> Result<Self, Error> {
+        let mut req = Self {
+            user_id,
+            current_password,
+            new_password,
+        };
+        req.validate()?;
+        req.new_password = utils::compute_password_hash(&req.new_password)?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct ForgotPasswordRequest {
+    #[validate(email)]
+    pub email: String,
+    pub expiration: u64,
+}
+
+impl ForgotPasswordRequest {
+    pub fn new(email: String, expiration: u64) -> Result<Self, Error> {
+        let req = Self { email, expiration };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+/// A freshly issued reset token, in plain text so it can be mailed out.
+#[derive(Debug, Clone)]
+pub struct PasswordResetToken {
+    pub username: String,
+    pub email: String,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct ResetPasswordRequest {
+    #[validate(length(min = 1))]
+    pub token: String,
+    #[validate(length(min = 8, max = 18))]
+    pub new_password: String,
+}
+
+impl ResetPasswordRequest {
+    pub fn new(token: String, new_password: String) -> Result<Self, Error> {
+        let mut req = Self {
+            token,
+            new_password,
+        };
+        req.validate()?;
+        req.new_password = utils::compute_password_hash(&req.new_password)?;
+        Ok(req)
+    }
+}
+
+/// A freshly issued email verification token, in plain text so it can be
+/// mailed out.
+#[derive(Debug, Clone)]
+pub struct EmailVerificationToken {
+    pub username: String,
+    pub email: String,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct VerifyEmailRequest {
+    #[validate(length(min = 1))]
+    pub token: String,
+}
+
+impl VerifyEmailRequest {
+    pub fn new(token: String) -> Result<Self, Error> {
+        let req = Self { token };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+/// Who may log in, and how hard repeated failures are slowed down.
+#[derive(Debug, Clone, Copy)]
+pub struct LoginPolicy {
+    /// Refuse users whose email address has not been verified yet.
+    pub require_verified_email: bool,
+    pub throttle: LoginThrottle,
+    /// Seconds a two-factor challenge stays valid.
+    pub challenge_expiration: u64,
+}
+
+/// How hard repeated login failures are slowed down.
+#[derive(Debug, Clone, Copy)]
+pub struct LoginThrottle {
+    pub max_failures: u32,
+    pub ip_max_failures: u32,
+    pub backoff_base: u64,
+    pub backoff_max: u64,
+    pub lockout_duration: u64,
+}
+
+impl LoginThrottle {
+    /// Wait before the attempt following the `failures`th failure in a row.
+    fn backoff(&self, failures: i32) -> Duration {
+        let exponent = failures.saturating_sub(1).clamp(0, 32) as u32;
+        let seconds = self
+            .backoff_base
+            .saturating_mul(1 << exponent)
+            .min(self.backoff_max);
+        Duration::seconds(seconds as i64)
+    }
+}
+
+/// Failed login bookkeeping for one username or client IP.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LoginAttempt {
+    pub key: String,
+    pub failures: i32,
+    pub last_failed_at: Option<DateTime<Utc>>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+impl LoginAttempt {
+    pub fn new(key: String) -> Self {
+        Self {
+            key,
+            failures: 0,
+            last_failed_at: None,
+            locked_until: None,
+        }
+    }
+
+    pub fn username_key(username: &str) -> String {
+        format!("user:{username}")
+    }
+
+    pub fn ip_key(ip: &str) -> String {
+        format!("ip:{ip}")
+    }
+
+    pub fn is_locked(&self, now: DateTime<Utc>) -> bool {
+        self.locked_until.is_some_and(|until| until > now)
+    }
+
+    /// Whether the backoff after the last failure is still running.
+    pub fn is_backing_off(&self, throttle: &LoginThrottle, now: DateTime<Utc>) -> bool {
+        match self.last_failed_at {
+            Some(last) if self.failures > 0 && self.locked_until.is_none() => {
+                last + throttle.backoff(self.failures) > now
+            }
+            _ => false,
+        }
+    }
+
+    /// Counts another failure, locking once `max_failures` is reached. A
+    /// lock that ran out, or failures older than a lockout, start over.
+    pub fn record_failure(&mut self, max_failures: u32, lockout_duration: u64, now: DateTime<Utc>) {
+        let lockout = Duration::seconds(lockout_duration as i64);
+        let lock_expired = self.locked_until.is_some_and(|until| until <= now);
+        let stale = self.last_failed_at.is_some_and(|last| now - last > lockout);
+        if lock_expired || stale {
+            self.failures = 0;
+            self.locked_until = None;
+        }
+        self.failures += 1;
+        self.last_failed_at = Some(now);
+        if self.failures as u32 >= max_failures {
+            self.locked_until = Some(now + lockout);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct UnlockUserRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub username: String,
+}
+
+impl UnlockUserRequest {
+    pub fn new(username: String) -> Result<Self, Error> {
+        let req = Self { username };
+        req.validate()?;
+        Ok(req)
+    }
+}
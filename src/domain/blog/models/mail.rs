@@ -0,0 +1,39 @@
+/// A plain text email handed to the `Mailer` port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl Email {
+    pub fn password_reset(to: String, username: &str, token: &str, expires_in: u64) -> Self {
+        Self {
+            to,
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Hi {username},\n\n\
+                 someone asked to reset the password of your account. If it was you, \
+                 send the token below to POST /api/auth/reset-password within {} minutes:\n\n\
+                 {token}\n\n\
+                 If you did not ask for this, you can ignore this email.\n",
+                expires_in / 60
+            ),
+        }
+    }
+
+    pub fn email_verification(to: String, username: &str, token: &str, expires_in: u64) -> Self {
+        Self {
+            to,
+            subject: "Verify your email address".to_string(),
+            body: format!(
+                "Hi {username},\n\n\
+                 thanks for signing up. To confirm this is your email address, \
+                 send the token below to POST /api/auth/verify-email within {} hours:\n\n\
+                 {token}\n\n\
+                 If you did not create an account, you can ignore this email.\n",
+                expires_in / 3600
+            ),
+        }
+    }
+}
@@ -1,2 +1,12 @@
+pub mod access_tokens;
+pub mod auth;
+pub mod mail;
+pub mod oidc;
+pub mod policies;
 pub mod posts;
+pub mod revisions;
+pub mod roles;
+pub mod search;
+pub mod tags;
+pub mod two_factor;
 pub mod users;
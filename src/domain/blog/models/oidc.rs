@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use validator::Validate;
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{auth::LoginPolicy, roles::Role},
+    },
+    utils::{jwt::JWT, token},
+};
+
+/// The user the identity provider vouches for, taken from a validated ID
+/// token.
+#[derive(Debug, Clone)]
+pub struct ExternalIdentity {
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub preferred_username: Option<String>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UserIdentity {
+    pub id: String,
+    pub user_id: String,
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Secrets of one authorization code flow, kept until the provider sends the
+/// user back.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct OidcLoginState {
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String,
+}
+
+impl OidcLoginState {
+    pub fn generate() -> Self {
+        Self {
+            state: token::generate_token(),
+            nonce: token::generate_token(),
+            code_verifier: token::generate_token(),
+        }
+    }
+
+    pub fn code_challenge(&self) -> String {
+        token::pkce_challenge(&self.code_verifier)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct StartOidcLoginRequest {
+    /// Seconds the provider has to send the user back.
+    pub expiration: u64,
+}
+
+impl StartOidcLoginRequest {
+    pub fn new(expiration: u64) -> Result<Self, Error> {
+        let req = Self { expiration };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct OidcCallbackRequest {
+    #[validate(length(min = 1))]
+    pub code: String,
+    #[validate(length(min = 1))]
+    pub state: String,
+    pub auto_provision: bool,
+    /// Role given to users created by `auto_provision`.
+    pub default_role: Role,
+    pub jwt: JWT,
+    pub expiration: u64,
+    pub refresh_expiration: u64,
+    pub policy: LoginPolicy,
+}
+
+impl OidcCallbackRequest {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        code: String,
+        state: String,
+        auto_provision: bool,
+        default_role: Role,
+        jwt: JWT,
+        expiration: u64,
+        refresh_expiration: u64,
+        policy: LoginPolicy,
+    ) -> Result<Self, Error> {
+        let req = Self {
+            code,
+            state,
+            auto_provision,
+            default_role,
+            jwt,
+            expiration,
+            refresh_expiration,
+            policy,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+/// Logs in the user linked to `identity`, linking or creating one first.
+#[derive(Debug, Clone)]
+pub struct IdentityLoginRequest {
+    pub identity: ExternalIdentity,
+    pub auto_provision: bool,
+    /// Role given to users created by `auto_provision`.
+    pub default_role: Role,
+    pub jwt: JWT,
+    pub expiration: u64,
+    pub refresh_expiration: u64,
+    pub policy: LoginPolicy,
+}
@@ -0,0 +1,180 @@
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::domain::blog::{
+    error::Error,
+    models::roles::{Role, ROLE_PREFIX},
+};
+
+/// A casbin `p` rule: `sub` may do `act` on `obj`. `obj` is a key pattern
+/// where `*` matches the rest of a path, `act` a regular expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Policy {
+    pub sub: String,
+    pub obj: String,
+    pub act: String,
+}
+
+impl From<Vec<String>> for Policy {
+    fn from(rule: Vec<String>) -> Self {
+        let mut rule = rule.into_iter();
+        Self {
+            sub: rule.next().unwrap_or_default(),
+            obj: rule.next().unwrap_or_default(),
+            act: rule.next().unwrap_or_default(),
+        }
+    }
+}
+
+/// A casbin `g` rule: `subject` inherits every permission of `role`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoleGrouping {
+    pub subject: String,
+    pub role: String,
+}
+
+impl From<Vec<String>> for RoleGrouping {
+    fn from(rule: Vec<String>) -> Self {
+        let mut rule = rule.into_iter();
+        Self {
+            subject: rule.next().unwrap_or_default(),
+            role: rule.next().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct PolicyRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub sub: String,
+    #[validate(length(min = 1, max = 255))]
+    pub obj: String,
+    #[validate(length(min = 1, max = 255), custom(function = "validate_act"))]
+    pub act: String,
+}
+
+impl PolicyRequest {
+    pub fn new(sub: String, obj: String, act: String) -> Result<Self, Error> {
+        let req = Self { sub, obj, act };
+        req.validate()?;
+        Ok(req)
+    }
+
+    pub fn rule(&self) -> Vec<String> {
+        vec![self.sub.clone(), self.obj.clone(), self.act.clone()]
+    }
+}
+
+/// Adds or removes a `g` rule. Only roles (`role:` subjects) can be
+/// inherited from, so one user never silently gains another's permissions.
+#[derive(Debug, Clone, Validate)]
+pub struct RoleGroupingRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub subject: String,
+    #[validate(length(min = 1, max = 100), custom(function = "validate_role_subject"))]
+    pub role: String,
+}
+
+impl RoleGroupingRequest {
+    pub fn new(subject: String, role: String) -> Result<Self, Error> {
+        let req = Self { subject, role };
+        req.validate()?;
+        if req.subject == req.role {
+            let mut errors = ValidationErrors::new();
+            errors.add("role", ValidationError::new("self_reference"));
+            return Err(errors.into());
+        }
+        Ok(req)
+    }
+
+    pub fn rule(&self) -> Vec<String> {
+        vec![self.subject.clone(), self.role.clone()]
+    }
+}
+
+/// Gives one of the built-in roles to a user, or takes it away.
+#[derive(Debug, Clone, Validate)]
+pub struct RoleAssignmentRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub username: String,
+    pub role: Role,
+}
+
+impl RoleAssignmentRequest {
+    pub fn new(username: String, role: &str) -> Result<Self, Error> {
+        let Some(role) = Role::from_name(role) else {
+            let mut errors = ValidationErrors::new();
+            errors.add("role", ValidationError::new("unknown_role"));
+            return Err(errors.into());
+        };
+        let req = Self { username, role };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct GetUserRolesRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub username: String,
+}
+
+impl GetUserRolesRequest {
+    pub fn new(username: String) -> Result<Self, Error> {
+        let req = Self { username };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+/// Roles of a user: those assigned directly, and every role reachable
+/// through inheritance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserRoles {
+    pub username: String,
+    pub roles: Vec<String>,
+    pub implicit_roles: Vec<String>,
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct ExplainPermissionRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub sub: String,
+    #[validate(length(min = 1, max = 255))]
+    pub obj: String,
+    #[validate(length(min = 1, max = 100))]
+    pub act: String,
+}
+
+impl ExplainPermissionRequest {
+    pub fn new(sub: String, obj: String, act: String) -> Result<Self, Error> {
+        let req = Self { sub, obj, act };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+/// The outcome of a permission check and why: the first rule granting it,
+/// through `sub` itself or one of `roles`. Rules only ever allow, so a
+/// denial means no rule matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionExplanation {
+    pub allowed: bool,
+    pub policy: Option<Policy>,
+    pub roles: Vec<String>,
+}
+
+/// `act` is evaluated as a regular expression by the enforcer, which panics
+/// on one that does not compile.
+fn validate_act(act: &str) -> Result<(), ValidationError> {
+    if regex::Regex::new(act).is_err() {
+        return Err(ValidationError::new("invalid_regex"));
+    }
+    Ok(())
+}
+
+fn validate_role_subject(role: &str) -> Result<(), ValidationError> {
+    if !role.starts_with(ROLE_PREFIX) {
+        return Err(ValidationError::new("not_a_role"));
+    }
+    Ok(())
+}
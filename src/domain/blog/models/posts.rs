@@ -1,15 +1,88 @@
-use chrono::{DateTime, Utc};
-use validator::Validate;
+use std::borrow::Cow;
 
-use crate::domain::blog::error::Error;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::{
+    domain::blog::{error::Error, markdown},
+    utils,
+};
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct Post {
     pub id: String,
     pub title: String,
+    pub slug: String,
     pub content: String,
+    /// Sanitized HTML rendered from `content`, cached on write.
+    pub content_html: Option<String>,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    #[sqlx(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "post_status", rename_all = "lowercase")]
+pub enum PostStatus {
+    Draft,
+    Published,
+    Archived,
+}
+
+impl Post {
+    /// The rendered content, falling back to rendering it now for posts
+    /// written before the HTML was cached.
+    pub fn html(&self) -> Cow<'_, str> {
+        match &self.content_html {
+            Some(html) => Cow::Borrowed(html),
+            None => Cow::Owned(markdown::render_markdown(&self.content)),
+        }
+    }
+}
+
+impl PostStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostStatus::Draft => "draft",
+            PostStatus::Published => "published",
+            PostStatus::Archived => "archived",
+        }
+    }
+
+    /// Posts move draft -> published -> archived, and may be taken back to
+    /// draft from either of the later states.
+    pub fn can_transition_to(&self, to: PostStatus) -> bool {
+        matches!(
+            (self, to),
+            (PostStatus::Draft, PostStatus::Published)
+                | (PostStatus::Published, PostStatus::Archived)
+                | (PostStatus::Published, PostStatus::Draft)
+                | (PostStatus::Archived, PostStatus::Draft)
+        )
+    }
+}
+
+impl std::fmt::Display for PostStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// How a tag filter with several tags is applied: posts carrying any one of
+/// them, or only posts carrying all of them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    #[default]
+    Any,
+    All,
 }
 
 #[derive(Debug, Clone, Validate)]
@@ -19,14 +92,32 @@ pub struct CreatePostRequest {
     #[validate(length(min = 1))]
     pub content: String,
     pub username: String,
+    pub slug: String,
+    pub content_html: String,
+    #[validate(custom(function = "validate_publish_at"))]
+    pub publish_at: Option<DateTime<Utc>>,
+    #[validate(length(max = 10), custom(function = "validate_tags"))]
+    pub tags: Vec<String>,
 }
 
 impl CreatePostRequest {
-    pub fn new(title: String, content: String, username: String) -> Result<Self, Error> {
+    pub fn new(
+        title: String,
+        content: String,
+        username: String,
+        publish_at: Option<DateTime<Utc>>,
+        tags: Vec<String>,
+    ) -> Result<Self, Error> {
+        let slug = utils::slugify(&title);
+        let content_html = markdown::render_markdown(&content);
         let req = Self {
             title,
             content,
             username,
+            slug,
+            content_html,
+            publish_at,
+            tags: normalize_tags(tags),
         };
         req.validate()?;
         Ok(req)
@@ -40,18 +131,42 @@ pub struct ListPostRequest {
     #[validate(range(min = 1, max = 50))]
     pub limit: u32,
     pub username: String,
+    pub status: Option<PostStatus>,
+    #[validate(length(max = 10), custom(function = "validate_tags"))]
+    pub tags: Vec<String>,
+    pub tag_match: TagMatch,
 }
 
 impl ListPostRequest {
-    pub fn new(offset: u32, limit: u32, username: String) -> Result<Self, Error> {
+    pub fn new(
+        offset: u32,
+        limit: u32,
+        username: String,
+        status: Option<PostStatus>,
+    ) -> Result<Self, Error> {
         let req = Self {
             offset,
             limit,
             username,
+            status,
+            tags: Vec::new(),
+            tag_match: TagMatch::default(),
         };
         req.validate()?;
         Ok(req)
     }
+
+    pub fn with_tags(mut self, tags: Vec<String>, tag_match: TagMatch) -> Result<Self, Error> {
+        self.tags = normalize_tags(tags);
+        self.tag_match = tag_match;
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Listing of another author's posts, which only ever includes published ones.
+    pub fn published(offset: u32, limit: u32, username: String) -> Result<Self, Error> {
+        Self::new(offset, limit, username, Some(PostStatus::Published))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -68,6 +183,13 @@ pub struct UpdatePostRequest {
     #[validate(length(min = 1))]
     pub content: String,
     pub username: String,
+    pub slug: String,
+    pub content_html: String,
+    #[validate(custom(function = "validate_publish_at"))]
+    pub publish_at: Option<DateTime<Utc>>,
+    /// `None` leaves the post's tags untouched.
+    #[validate(length(max = 10), custom(function = "validate_tags"))]
+    pub tags: Option<Vec<String>>,
 }
 
 impl UpdatePostRequest {
@@ -76,18 +198,119 @@ impl UpdatePostRequest {
         title: String,
         content: String,
         username: String,
+        publish_at: Option<DateTime<Utc>>,
+        tags: Option<Vec<String>>,
     ) -> Result<Self, Error> {
+        let slug = utils::slugify(&title);
+        let content_html = markdown::render_markdown(&content);
         let req = Self {
             id,
             title,
             content,
             username,
+            slug,
+            content_html,
+            publish_at,
+            tags: tags.map(normalize_tags),
         };
         req.validate()?;
         Ok(req)
     }
 }
 
+#[derive(Debug, Clone, Validate)]
+pub struct ChangePostStatusRequest {
+    pub id: String,
+    pub username: String,
+    pub status: PostStatus,
+}
+
+impl ChangePostStatusRequest {
+    pub fn new(id: String, username: String, status: PostStatus) -> Result<Self, Error> {
+        let req = Self {
+            id,
+            username,
+            status,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct GetPostBySlugRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub username: String,
+    #[validate(length(min = 1))]
+    pub slug: String,
+    pub status: Option<PostStatus>,
+    /// The authenticated caller. Posts that are not published are only found
+    /// for their author and for roles that may read every post.
+    pub viewer: Option<String>,
+}
+
+impl GetPostBySlugRequest {
+    pub fn new(username: String, slug: String, viewer: String) -> Result<Self, Error> {
+        let req = Self {
+            username,
+            slug,
+            status: None,
+            viewer: Some(viewer),
+        };
+        req.validate()?;
+        Ok(req)
+    }
+
+    /// Lookup on behalf of an anonymous reader, which only ever finds published posts.
+    pub fn published(username: String, slug: String) -> Result<Self, Error> {
+        let req = Self {
+            username,
+            slug,
+            status: Some(PostStatus::Published),
+            viewer: None,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+/// A slug either names a post directly, or is an old slug of a post that has
+/// since been renamed, in which case callers should redirect to the new one.
+#[derive(Debug, Clone)]
+pub enum GetPostBySlugResponse {
+    Found(Post),
+    Moved { username: String, slug: String },
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct GetPostRequest {
+    #[validate(length(min = 1))]
+    pub id: String,
+    pub username: String,
+}
+
+impl GetPostRequest {
+    pub fn new(id: String, username: String) -> Result<Self, Error> {
+        let req = Self { id, username };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct GetPublishedPostRequest {
+    #[validate(length(min = 1))]
+    pub id: String,
+}
+
+impl GetPublishedPostRequest {
+    pub fn new(id: String) -> Result<Self, Error> {
+        let req = Self { id };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
 #[derive(Debug, Clone, Validate)]
 pub struct DeletePostRequest {
     pub id: String,
@@ -116,3 +339,91 @@ impl BatchDeletePostRequest {
         Ok(req)
     }
 }
+
+#[derive(Debug, Clone, Validate)]
+pub struct ListTrashedPostRequest {
+    #[validate(range(min = 0))]
+    pub offset: u32,
+    #[validate(range(min = 1, max = 50))]
+    pub limit: u32,
+    pub username: String,
+    /// Whose trash to list, `username`'s own when unset. Other authors'
+    /// trash needs a role that can read every post.
+    pub author: Option<String>,
+}
+
+impl ListTrashedPostRequest {
+    pub fn new(
+        offset: u32,
+        limit: u32,
+        username: String,
+        author: Option<String>,
+    ) -> Result<Self, Error> {
+        let req = Self {
+            offset,
+            limit,
+            username,
+            author,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+/// Identifies a post in its author's trash, for restoring or permanently
+/// deleting it.
+#[derive(Debug, Clone, Validate)]
+pub struct TrashedPostRequest {
+    pub id: String,
+    pub username: String,
+}
+
+impl TrashedPostRequest {
+    pub fn new(id: String, username: String) -> Result<Self, Error> {
+        let req = Self { id, username };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PurgeTrashRequest {
+    pub deleted_before: DateTime<Utc>,
+}
+
+impl PurgeTrashRequest {
+    pub fn new(retention_days: u32) -> Self {
+        Self {
+            deleted_before: Utc::now() - Duration::days(retention_days.into()),
+        }
+    }
+}
+
+fn validate_publish_at(publish_at: &DateTime<Utc>) -> Result<(), ValidationError> {
+    if *publish_at <= Utc::now() {
+        return Err(ValidationError::new("publish_at_in_past"));
+    }
+    Ok(())
+}
+
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut res: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !res.contains(&tag) {
+            res.push(tag);
+        }
+    }
+    res
+}
+
+fn validate_tags(tags: &[String]) -> Result<(), ValidationError> {
+    if tags.iter().any(|tag| tag.chars().count() > 30) {
+        return Err(ValidationError::new("tag_too_long"));
+    }
+    // The `tag` query parameter of the listings is comma separated.
+    if tags.iter().any(|tag| tag.contains(',')) {
+        return Err(ValidationError::new("tag_contains_comma"));
+    }
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use similar::TextDiff;
+use validator::Validate;
+
+use crate::domain::blog::error::Error;
+
+const DIFF_CONTEXT_RADIUS: usize = 3;
+
+/// A snapshot of a post's title and content. Every write to a post stores
+/// one, so the latest revision always matches the post itself.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PostRevision {
+    pub id: String,
+    pub post_id: String,
+    pub revision: i32,
+    pub title: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct ListPostRevisionsRequest {
+    pub post_id: String,
+    pub username: String,
+}
+
+impl ListPostRevisionsRequest {
+    pub fn new(post_id: String, username: String) -> Result<Self, Error> {
+        let req = Self { post_id, username };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct GetPostRevisionRequest {
+    pub post_id: String,
+    pub username: String,
+    #[validate(range(min = 1))]
+    pub revision: i32,
+}
+
+impl GetPostRevisionRequest {
+    pub fn new(post_id: String, username: String, revision: i32) -> Result<Self, Error> {
+        let req = Self {
+            post_id,
+            username,
+            revision,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct DiffPostRevisionsRequest {
+    pub post_id: String,
+    pub username: String,
+    #[validate(range(min = 1))]
+    pub from: i32,
+    #[validate(range(min = 1))]
+    pub to: i32,
+}
+
+impl DiffPostRevisionsRequest {
+    pub fn new(post_id: String, username: String, from: i32, to: i32) -> Result<Self, Error> {
+        let req = Self {
+            post_id,
+            username,
+            from,
+            to,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PostRevisionDiff {
+    pub from: PostRevision,
+    pub to: PostRevision,
+    pub diff: String,
+}
+
+impl PostRevisionDiff {
+    /// Diffs the revisions line by line, with the title as a first line so a
+    /// renamed post does not show an empty diff.
+    pub fn new(from: PostRevision, to: PostRevision) -> Self {
+        let old = format!("title: {}\n\n{}", from.title, from.content);
+        let new = format!("title: {}\n\n{}", to.title, to.content);
+        let diff = TextDiff::from_lines(&old, &new)
+            .unified_diff()
+            .context_radius(DIFF_CONTEXT_RADIUS)
+            .header(
+                &format!("revision {}", from.revision),
+                &format!("revision {}", to.revision),
+            )
+            .to_string();
+        Self { from, to, diff }
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct RestorePostRevisionRequest {
+    pub post_id: String,
+    pub username: String,
+    #[validate(range(min = 1))]
+    pub revision: i32,
+}
+
+impl RestorePostRevisionRequest {
+    pub fn new(post_id: String, username: String, revision: i32) -> Result<Self, Error> {
+        let req = Self {
+            post_id,
+            username,
+            revision,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
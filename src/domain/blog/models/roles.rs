@@ -0,0 +1,117 @@
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::{Deserialize, Serialize};
+
+/// Prefix of role subjects in casbin rules, e.g. `role:admin`. User subjects
+/// never contain `:`, see [`user_subject`], so a user cannot pose as a role.
+pub const ROLE_PREFIX: &str = "role:";
+
+/// Escaped in path segments of casbin objects: what cannot appear raw in a
+/// URL path, plus `%`, `/` and the `*` wildcard of `keyMatch`.
+const OBJECT_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}')
+    .add(b'%')
+    .add(b'/')
+    .add(b'*');
+
+/// Casbin object guarding posts owned by other users; see [`PostAccess`].
+pub const ANY_POST_OBJECT: &str = "posts:any";
+
+/// Roles form a chain, each inheriting the permissions of the next:
+/// admin -> editor -> author -> reader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Manages users, roles and everything editors can do.
+    Admin,
+    /// Manages posts of every author.
+    Editor,
+    /// Writes their own posts.
+    Author,
+    /// Reads their own posts but cannot write.
+    Reader,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [Role::Admin, Role::Editor, Role::Author, Role::Reader];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Editor => "editor",
+            Role::Author => "author",
+            Role::Reader => "reader",
+        }
+    }
+
+    /// The subject this role has in casbin rules.
+    pub fn subject(&self) -> String {
+        format!("{ROLE_PREFIX}{}", self.as_str())
+    }
+
+    pub fn from_name(name: &str) -> Option<Role> {
+        Role::ALL.into_iter().find(|role| role.as_str() == name)
+    }
+
+    pub fn from_subject(subject: &str) -> Option<Role> {
+        Role::from_name(subject.strip_prefix(ROLE_PREFIX)?)
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+/// What is done to a post owned by someone else, checked against
+/// [`ANY_POST_OBJECT`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostAccess {
+    Read,
+    Write,
+}
+
+impl PostAccess {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostAccess::Read => "read",
+            PostAccess::Write => "write",
+        }
+    }
+}
+
+/// The subject of a user in casbin rules: the username with `%` and `:`
+/// percent-encoded, so it cannot collide with a role.
+pub fn user_subject(username: &str) -> String {
+    username.replace('%', "%25").replace(':', "%3A")
+}
+
+/// The casbin object of a user's account, matched by the paths
+/// [`casbin_path`] gives.
+pub fn user_object(username: &str) -> String {
+    format!(
+        "/api/users/{}",
+        utf8_percent_encode(username, OBJECT_SEGMENT)
+    )
+}
+
+/// A request path as checked against casbin objects: each segment decoded
+/// and encoded again the same way as in [`user_object`], so a `*` in the
+/// path is never a wildcard and equivalent encodings are checked alike.
+pub fn casbin_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match percent_decode_str(segment).decode_utf8() {
+            Ok(segment) => utf8_percent_encode(&segment, OBJECT_SEGMENT).to_string(),
+            Err(_) => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
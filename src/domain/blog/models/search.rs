@@ -0,0 +1,131 @@
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::domain::blog::error::Error;
+
+use super::posts::Post;
+
+#[derive(Debug, Clone, Validate)]
+pub struct SearchPostRequest {
+    #[validate(length(min = 1, max = 200))]
+    pub q: String,
+    #[validate(range(min = 0))]
+    pub offset: u32,
+    #[validate(range(min = 1, max = 50))]
+    pub limit: u32,
+    pub username: String,
+    /// `q` translated to a Postgres `to_tsquery` expression.
+    pub tsquery: String,
+}
+
+impl SearchPostRequest {
+    pub fn new(q: String, offset: u32, limit: u32, username: String) -> Result<Self, Error> {
+        let tsquery = to_tsquery(&q);
+        let req = Self {
+            q,
+            offset,
+            limit,
+            username,
+            tsquery,
+        };
+        req.validate()?;
+        if req.tsquery.is_empty() {
+            let mut errors = ValidationErrors::new();
+            errors.add("q", ValidationError::new("no_search_terms"));
+            return Err(errors.into());
+        }
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SearchPostResult {
+    #[sqlx(flatten)]
+    pub post: Post,
+    pub rank: f32,
+    pub title_highlight: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchPostResponse {
+    pub total: u64,
+    pub results: Vec<SearchPostResult>,
+}
+
+/// Builds a `to_tsquery` expression in which every term must match. Text in
+/// double quotes is searched as a phrase and a trailing `*` turns a term into
+/// a prefix match. Anything but letters and digits is dropped, so the result
+/// is always a valid query.
+fn to_tsquery(q: &str) -> String {
+    let mut parts = Vec::new();
+    for (i, segment) in q.split('"').enumerate() {
+        if i % 2 == 1 {
+            parts.extend(phrase(segment, false));
+            continue;
+        }
+        for token in segment.split_whitespace() {
+            parts.extend(phrase(token, token.ends_with('*')));
+        }
+    }
+    parts.join(" & ")
+}
+
+fn phrase(text: &str, prefix: bool) -> Option<String> {
+    let mut words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if prefix {
+        words.last_mut()?.push_str(":*");
+    }
+    match words.len() {
+        0 => None,
+        1 => words.pop(),
+        _ => Some(format!("({})", words.join(" <-> "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_term_must_match() {
+        assert_eq!(to_tsquery("Rust  async"), "rust & async");
+    }
+
+    #[test]
+    fn quoted_text_is_a_phrase() {
+        assert_eq!(
+            to_tsquery(r#""hexagonal architecture" rust"#),
+            "(hexagonal <-> architecture) & rust"
+        );
+        assert_eq!(to_tsquery(r#"rust "async"#), "rust & async");
+    }
+
+    #[test]
+    fn trailing_star_is_a_prefix_match() {
+        assert_eq!(to_tsquery("hexa* rust"), "hexa:* & rust");
+        assert_eq!(to_tsquery("axum-rs*"), "(axum <-> rs:*)");
+        assert_eq!(to_tsquery("*"), "");
+    }
+
+    #[test]
+    fn operators_in_the_input_are_dropped() {
+        assert_eq!(to_tsquery("a & b | !c"), "a & b & c");
+        assert_eq!(to_tsquery("(rust) <-> go:*"), "rust & go:*");
+        assert_eq!(to_tsquery("it's"), "(it <-> s)");
+        assert_eq!(
+            to_tsquery("'; DROP TABLE posts; --"),
+            "drop & table & posts"
+        );
+        assert_eq!(to_tsquery(r#"&|!<->():*'""#), "");
+    }
+
+    #[test]
+    fn queries_without_terms_are_rejected() {
+        let req = SearchPostRequest::new("&& !".to_string(), 0, 10, "alice".to_string());
+        assert!(matches!(req, Err(Error::ValidationError(_))));
+    }
+}
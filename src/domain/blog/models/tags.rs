@@ -0,0 +1,5 @@
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Tag {
+    pub name: String,
+    pub post_count: i64,
+}
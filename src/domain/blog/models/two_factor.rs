@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use validator::Validate;
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::auth::{LoginPolicy, LoginThrottle},
+    },
+    utils::jwt::JWT,
+};
+
+/// A freshly generated TOTP secret, not yet active until confirmed.
+#[derive(Debug, Clone)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RecoveryCode {
+    pub id: String,
+    pub user_id: String,
+    pub code_hash: String,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Issued by `login` to users with 2FA, exchanged for tokens together with a
+/// valid code.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LoginChallenge {
+    pub id: String,
+    pub user_id: String,
+    pub token_hash: String,
+    pub failures: i32,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct EnrollTotpRequest {
+    pub user_id: String,
+    #[validate(length(min = 1))]
+    pub issuer: String,
+}
+
+impl EnrollTotpRequest {
+    pub fn new(user_id: String, issuer: String) -> Result<Self, Error> {
+        let req = Self { user_id, issuer };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct ConfirmTotpRequest {
+    pub user_id: String,
+    #[validate(length(min = 1))]
+    pub code: String,
+    /// How many recovery codes to hand out.
+    pub recovery_codes: usize,
+}
+
+impl ConfirmTotpRequest {
+    pub fn new(user_id: String, code: String, recovery_codes: usize) -> Result<Self, Error> {
+        let req = Self {
+            user_id,
+            code,
+            recovery_codes,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct DisableTotpRequest {
+    pub user_id: String,
+    /// A TOTP code or an unused recovery code.
+    #[validate(length(min = 1))]
+    pub code: String,
+    /// Wrong codes count as failed logins of the user.
+    pub throttle: LoginThrottle,
+}
+
+impl DisableTotpRequest {
+    pub fn new(user_id: String, code: String, throttle: LoginThrottle) -> Result<Self, Error> {
+        let req = Self {
+            user_id,
+            code,
+            throttle,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct VerifyTwoFactorRequest {
+    #[validate(length(min = 1))]
+    pub challenge_token: String,
+    /// A TOTP code or an unused recovery code.
+    #[validate(length(min = 1))]
+    pub code: String,
+    pub jwt: JWT,
+    pub expiration: u64,
+    pub refresh_expiration: u64,
+    /// Wrong codes count as failed logins of the user.
+    pub policy: LoginPolicy,
+}
+
+impl VerifyTwoFactorRequest {
+    pub fn new(
+        challenge_token: String,
+        code: String,
+        jwt: JWT,
+        expiration: u64,
+        refresh_expiration: u64,
+        policy: LoginPolicy,
+    ) -> Result<Self, Error> {
+        let req = Self {
+            challenge_token,
+            code,
+            jwt,
+            expiration,
+            refresh_expiration,
+            policy,
+        };
+        req.validate()?;
+        Ok(req)
+    }
+}
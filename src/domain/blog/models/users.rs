@@ -1,7 +1,13 @@
 use chrono::{DateTime, Utc};
-use validator::Validate;
+use validator::{Validate, ValidationError, ValidationErrors};
 
-use crate::{domain::blog::error::Error, utils};
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{auth::LoginPolicy, roles::Role},
+    },
+    utils::{self, jwt::JWT},
+};
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct User {
@@ -10,35 +16,88 @@ pub struct User {
     pub password: String,
     pub email: Option<String>,
     pub phone: Option<String>,
+    /// Set by "log out all sessions"; tokens issued before it are rejected.
+    pub tokens_valid_after: Option<DateTime<Utc>>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    /// Base32 TOTP secret, pending until `totp_enabled_at` is set.
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<DateTime<Utc>>,
+    /// Time step of the last accepted TOTP code.
+    pub totp_last_step: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl User {
+    /// Whether a token issued at `iat` (seconds since the epoch) survived the
+    /// user's last "log out all sessions". `tokens_valid_after` is stored in
+    /// whole seconds, so a token issued right after it is still accepted.
+    pub fn accepts_token_issued_at(&self, iat: u64) -> bool {
+        self.tokens_valid_after
+            .is_none_or(|after| iat as i64 >= after.timestamp())
+    }
+
+    pub fn is_email_verified(&self) -> bool {
+        self.email_verified_at.is_some()
+    }
+
+    /// Refuses tokens to an unverified account when the policy asks for a
+    /// verified email; every path that issues tokens goes through here.
+    pub fn ensure_may_sign_in(&self, require_verified_email: bool) -> Result<(), Error> {
+        if require_verified_email && !self.is_email_verified() {
+            return Err(Error::Forbidden(
+                "email address is not verified".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn is_two_factor_enabled(&self) -> bool {
+        self.totp_enabled_at.is_some()
+    }
+}
+
 #[derive(Debug, Clone, Validate)]
 pub struct CreateUserRequest {
-    #[validate(length(min = 1, max = 50))]
+    #[validate(length(min = 1, max = 50), custom(function = "validate_username"))]
     pub username: String,
     #[validate(email)]
     pub email: Option<String>,
     pub phone: Option<String>,
     #[validate(length(min = 8, max = 18))]
     pub password: String,
+    /// Seconds the verification token mailed to `email` stays valid.
+    pub verification_expiration: u64,
+    /// Role the new user starts with.
+    pub role: Role,
 }
 
 impl CreateUserRequest {
+    /// `email_required` is set when an unverified account would be locked
+    /// out of something, so registering without an email makes no sense.
     pub fn new(
         username: String,
         email: Option<String>,
         phone: Option<String>,
         password: String,
+        email_required: bool,
+        verification_expiration: u64,
+        role: Role,
     ) -> Result<Self, Error> {
         let mut req = Self {
             username,
             email,
             phone,
             password,
+            verification_expiration,
+            role,
         };
         req.validate()?;
+        if email_required && req.email.is_none() {
+            let mut errors = ValidationErrors::new();
+            errors.add("email", ValidationError::new("required"));
+            return Err(errors.into());
+        }
         let password = utils::compute_password_hash(&req.password)?;
         req.password = password;
         Ok(req)
@@ -78,22 +137,32 @@ pub struct LoginRequest {
     pub username: String,
     #[validate(length(min = 8, max = 18))]
     pub password: String,
-    pub jwt_secret: String,
+    pub jwt: JWT,
     pub expiration: u64,
+    pub refresh_expiration: u64,
+    /// Client IP, throttled alongside the username when known.
+    pub ip: Option<String>,
+    pub policy: LoginPolicy,
 }
 
 impl LoginRequest {
     pub fn new(
         username: String,
         password: String,
-        jwt_secret: String,
+        jwt: JWT,
         expiration: u64,
+        refresh_expiration: u64,
+        ip: Option<String>,
+        policy: LoginPolicy,
     ) -> Result<Self, Error> {
         let req = Self {
             username,
             password,
-            jwt_secret,
+            jwt,
             expiration,
+            refresh_expiration,
+            ip,
+            policy,
         };
         req.validate()?;
         Ok(req)
@@ -113,3 +182,18 @@ impl DeleteUserRequest {
         Ok(req)
     }
 }
+
+/// `*`, `:` and `/` mean something in casbin rules and in the
+/// `/api/users/<username>` paths, so they are kept out of usernames. Rules
+/// escape usernames too, see `user_subject` and `user_object`, which covers
+/// names created before this check.
+fn validate_username(username: &str) -> Result<(), ValidationError> {
+    if !username.chars().all(is_username_char) {
+        return Err(ValidationError::new("invalid_character"));
+    }
+    Ok(())
+}
+
+pub fn is_username_char(c: char) -> bool {
+    !matches!(c, '*' | ':' | '/')
+}
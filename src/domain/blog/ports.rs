@@ -3,9 +3,39 @@ use std::future::Future;
 use super::{
     error::Error,
     models::{
+        access_tokens::{
+            AccessToken, CreateAccessTokenRequest, ListAccessTokensRequest, NewAccessToken,
+            RevokeAccessTokenRequest,
+        },
+        auth::{
+            ChangePasswordRequest, EmailVerificationToken, ForgotPasswordRequest, LoginResponse,
+            LogoutAllRequest, LogoutRequest, PasswordResetToken, RefreshTokenRequest,
+            ResetPasswordRequest, TokenPair, UnlockUserRequest, VerifyEmailRequest,
+        },
+        mail::Email,
+        oidc::{
+            ExternalIdentity, IdentityLoginRequest, OidcCallbackRequest, OidcLoginState,
+            StartOidcLoginRequest,
+        },
+        policies::{
+            ExplainPermissionRequest, GetUserRolesRequest, PermissionExplanation, Policy,
+            PolicyRequest, RoleAssignmentRequest, RoleGrouping, RoleGroupingRequest, UserRoles,
+        },
         posts::{
-            BatchDeletePostRequest, CreatePostRequest, DeletePostRequest, ListPostRequest,
-            ListPostResponse, Post, UpdatePostRequest,
+            BatchDeletePostRequest, ChangePostStatusRequest, CreatePostRequest, DeletePostRequest,
+            GetPostBySlugRequest, GetPostBySlugResponse, GetPostRequest, GetPublishedPostRequest,
+            ListPostRequest, ListPostResponse, ListTrashedPostRequest, Post, PurgeTrashRequest,
+            TrashedPostRequest, UpdatePostRequest,
+        },
+        revisions::{
+            DiffPostRevisionsRequest, GetPostRevisionRequest, ListPostRevisionsRequest,
+            PostRevision, PostRevisionDiff, RestorePostRevisionRequest,
+        },
+        search::{SearchPostRequest, SearchPostResponse},
+        tags::Tag,
+        two_factor::{
+            ConfirmTotpRequest, DisableTotpRequest, EnrollTotpRequest, TotpEnrollment,
+            VerifyTwoFactorRequest,
         },
         users::{
             CreateUserRequest, DeleteUserRequest, GetUserByIdRequest, GetUserRequest, LoginRequest,
@@ -25,11 +55,61 @@ pub trait BlogService: Clone + Send + Sync + 'static {
         req: ListPostRequest,
     ) -> impl Future<Output = Result<ListPostResponse, Error>> + Send;
 
+    fn search_post(
+        &self,
+        req: &SearchPostRequest,
+    ) -> impl Future<Output = Result<SearchPostResponse, Error>> + Send;
+
+    fn get_post(&self, req: &GetPostRequest) -> impl Future<Output = Result<Post, Error>> + Send;
+
     fn update_post(
         &self,
         req: &UpdatePostRequest,
     ) -> impl Future<Output = Result<Post, Error>> + Send;
 
+    fn change_post_status(
+        &self,
+        req: &ChangePostStatusRequest,
+    ) -> impl Future<Output = Result<Post, Error>> + Send;
+
+    /// Publishes every draft whose `publish_at` has passed, returning how
+    /// many posts went live.
+    fn publish_due_posts(&self) -> impl Future<Output = Result<u64, Error>> + Send;
+
+    fn get_post_by_slug(
+        &self,
+        req: &GetPostBySlugRequest,
+    ) -> impl Future<Output = Result<GetPostBySlugResponse, Error>> + Send;
+
+    /// Fetches a post by id for anonymous readers; anything not published is
+    /// reported as not found.
+    fn get_published_post(
+        &self,
+        req: &GetPublishedPostRequest,
+    ) -> impl Future<Output = Result<Post, Error>> + Send;
+
+    fn list_tags(&self) -> impl Future<Output = Result<Vec<Tag>, Error>> + Send;
+
+    fn list_post_revisions(
+        &self,
+        req: &ListPostRevisionsRequest,
+    ) -> impl Future<Output = Result<Vec<PostRevision>, Error>> + Send;
+
+    fn get_post_revision(
+        &self,
+        req: &GetPostRevisionRequest,
+    ) -> impl Future<Output = Result<PostRevision, Error>> + Send;
+
+    fn diff_post_revisions(
+        &self,
+        req: &DiffPostRevisionsRequest,
+    ) -> impl Future<Output = Result<PostRevisionDiff, Error>> + Send;
+
+    fn restore_post_revision(
+        &self,
+        req: &RestorePostRevisionRequest,
+    ) -> impl Future<Output = Result<Post, Error>> + Send;
+
     fn delete_post(
         &self,
         req: &DeletePostRequest,
@@ -40,6 +120,26 @@ pub trait BlogService: Clone + Send + Sync + 'static {
         req: &BatchDeletePostRequest,
     ) -> impl Future<Output = Result<(), Error>> + Send;
 
+    fn list_trashed_posts(
+        &self,
+        req: &ListTrashedPostRequest,
+    ) -> impl Future<Output = Result<ListPostResponse, Error>> + Send;
+
+    fn restore_trashed_post(
+        &self,
+        req: &TrashedPostRequest,
+    ) -> impl Future<Output = Result<Post, Error>> + Send;
+
+    fn delete_trashed_post(
+        &self,
+        req: &TrashedPostRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn purge_trash(
+        &self,
+        req: &PurgeTrashRequest,
+    ) -> impl Future<Output = Result<u64, Error>> + Send;
+
     fn create_user(
         &self,
         req: &CreateUserRequest,
@@ -57,7 +157,125 @@ pub trait BlogService: Clone + Send + Sync + 'static {
 
     fn get_user(&self, req: &GetUserRequest) -> impl Future<Output = Result<User, Error>> + Send;
 
-    fn login(&self, req: &LoginRequest) -> impl Future<Output = Result<String, Error>> + Send;
+    /// Checks the credentials, throttling repeated failures per username and
+    /// client IP. Unknown users and wrong passwords fail the same way. Users
+    /// with 2FA get a challenge instead of tokens.
+    fn login(
+        &self,
+        req: &LoginRequest,
+    ) -> impl Future<Output = Result<LoginResponse, Error>> + Send;
+
+    /// Completes a login challenge with a TOTP or recovery code.
+    fn verify_two_factor(
+        &self,
+        req: &VerifyTwoFactorRequest,
+    ) -> impl Future<Output = Result<TokenPair, Error>> + Send;
+
+    /// Generates a new TOTP secret; 2FA stays off until it is confirmed.
+    fn enroll_totp(
+        &self,
+        req: &EnrollTotpRequest,
+    ) -> impl Future<Output = Result<TotpEnrollment, Error>> + Send;
+
+    /// Turns 2FA on with a first valid code and returns the recovery codes,
+    /// which are never shown again.
+    fn confirm_totp(
+        &self,
+        req: &ConfirmTotpRequest,
+    ) -> impl Future<Output = Result<Vec<String>, Error>> + Send;
+
+    fn disable_totp(
+        &self,
+        req: &DisableTotpRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Clears the failed login count and lockout of a user.
+    fn unlock_user(
+        &self,
+        req: &UnlockUserRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Exchanges a refresh token for a new token pair. Replaying a token that
+    /// was already used revokes every token descended from the same login.
+    fn refresh_token(
+        &self,
+        req: &RefreshTokenRequest,
+    ) -> impl Future<Output = Result<TokenPair, Error>> + Send;
+
+    /// Revokes the access token identified by `req.jti`, and the refresh
+    /// token family of the same session when one is given.
+    fn logout(&self, req: &LogoutRequest) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Revokes every access and refresh token issued to the user so far.
+    fn logout_all(&self, req: &LogoutAllRequest) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn is_token_revoked(&self, jti: &str) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Drops revocation records and refresh tokens that have expired anyway,
+    /// returning how many rows were removed.
+    fn purge_expired_tokens(&self) -> impl Future<Output = Result<u64, Error>> + Send;
+
+    /// Replaces the password after checking the current one, and logs out
+    /// every session.
+    fn change_password(
+        &self,
+        req: &ChangePasswordRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Mails a reset token to every account using the address. Succeeds
+    /// whether or not any account does, so addresses cannot be probed.
+    fn forgot_password(
+        &self,
+        req: &ForgotPasswordRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn reset_password(
+        &self,
+        req: &ResetPasswordRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Marks the email address the token was mailed to as verified.
+    fn verify_email(
+        &self,
+        req: &VerifyEmailRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Creates a personal access token; the secret is only returned here.
+    fn create_access_token(
+        &self,
+        req: &CreateAccessTokenRequest,
+    ) -> impl Future<Output = Result<NewAccessToken, Error>> + Send;
+
+    fn list_access_tokens(
+        &self,
+        req: &ListAccessTokensRequest,
+    ) -> impl Future<Output = Result<Vec<AccessToken>, Error>> + Send;
+
+    fn revoke_access_token(
+        &self,
+        req: &RevokeAccessTokenRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Resolves a personal access token presented by a client, recording
+    /// that it was used. Unknown and expired tokens are `Unauthorized`.
+    fn authenticate_access_token(
+        &self,
+        token: &str,
+    ) -> impl Future<Output = Result<AccessToken, Error>> + Send;
+
+    /// Starts an authorization code + PKCE flow and returns the provider URL
+    /// to send the user to.
+    fn start_oidc_login(
+        &self,
+        req: &StartOidcLoginRequest,
+    ) -> impl Future<Output = Result<String, Error>> + Send;
+
+    /// Completes the flow when the provider sends the user back, logging in
+    /// the linked user, or asking for their second factor.
+    fn finish_oidc_login(
+        &self,
+        req: &OidcCallbackRequest,
+    ) -> impl Future<Output = Result<LoginResponse, Error>> + Send;
 
     fn check_permission(
         &self,
@@ -65,6 +283,51 @@ pub trait BlogService: Clone + Send + Sync + 'static {
         obj: &str,
         act: &str,
     ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    fn list_policies(&self) -> impl Future<Output = Result<Vec<Policy>, Error>> + Send;
+
+    /// Adds a `p` rule; an identical rule is a `Conflict`.
+    fn create_policy(
+        &self,
+        req: &PolicyRequest,
+    ) -> impl Future<Output = Result<Policy, Error>> + Send;
+
+    fn delete_policy(&self, req: &PolicyRequest) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn list_role_groupings(&self) -> impl Future<Output = Result<Vec<RoleGrouping>, Error>> + Send;
+
+    /// Adds a `g` rule; an identical rule is a `Conflict`.
+    fn create_role_grouping(
+        &self,
+        req: &RoleGroupingRequest,
+    ) -> impl Future<Output = Result<RoleGrouping, Error>> + Send;
+
+    fn delete_role_grouping(
+        &self,
+        req: &RoleGroupingRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn get_user_roles(
+        &self,
+        req: &GetUserRolesRequest,
+    ) -> impl Future<Output = Result<UserRoles, Error>> + Send;
+
+    /// Gives the user a role; assigning a role they already have is a no-op.
+    fn assign_role(
+        &self,
+        req: &RoleAssignmentRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn revoke_role(
+        &self,
+        req: &RoleAssignmentRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Reports whether `sub` may do `act` on `obj`, and which rule allows it.
+    fn explain_permission(
+        &self,
+        req: &ExplainPermissionRequest,
+    ) -> impl Future<Output = Result<PermissionExplanation, Error>> + Send;
 }
 
 pub trait BlogRepository: Clone + Send + Sync + 'static {
@@ -78,11 +341,52 @@ pub trait BlogRepository: Clone + Send + Sync + 'static {
         req: ListPostRequest,
     ) -> impl Future<Output = Result<ListPostResponse, Error>> + Send;
 
+    fn search_post(
+        &self,
+        req: &SearchPostRequest,
+    ) -> impl Future<Output = Result<SearchPostResponse, Error>> + Send;
+
+    fn get_post(&self, req: &GetPostRequest) -> impl Future<Output = Result<Post, Error>> + Send;
+
     fn update_post(
         &self,
         req: &UpdatePostRequest,
     ) -> impl Future<Output = Result<Post, Error>> + Send;
 
+    fn change_post_status(
+        &self,
+        req: &ChangePostStatusRequest,
+    ) -> impl Future<Output = Result<Post, Error>> + Send;
+
+    fn publish_due_posts(&self) -> impl Future<Output = Result<u64, Error>> + Send;
+
+    fn get_post_by_slug(
+        &self,
+        req: &GetPostBySlugRequest,
+    ) -> impl Future<Output = Result<GetPostBySlugResponse, Error>> + Send;
+
+    fn get_published_post(
+        &self,
+        req: &GetPublishedPostRequest,
+    ) -> impl Future<Output = Result<Post, Error>> + Send;
+
+    fn list_tags(&self) -> impl Future<Output = Result<Vec<Tag>, Error>> + Send;
+
+    fn list_post_revisions(
+        &self,
+        req: &ListPostRevisionsRequest,
+    ) -> impl Future<Output = Result<Vec<PostRevision>, Error>> + Send;
+
+    fn get_post_revision(
+        &self,
+        req: &GetPostRevisionRequest,
+    ) -> impl Future<Output = Result<PostRevision, Error>> + Send;
+
+    fn restore_post_revision(
+        &self,
+        req: &RestorePostRevisionRequest,
+    ) -> impl Future<Output = Result<Post, Error>> + Send;
+
     fn delete_post(
         &self,
         req: &DeletePostRequest,
@@ -93,6 +397,26 @@ pub trait BlogRepository: Clone + Send + Sync + 'static {
         req: &BatchDeletePostRequest,
     ) -> impl Future<Output = Result<(), Error>> + Send;
 
+    fn list_trashed_posts(
+        &self,
+        req: &ListTrashedPostRequest,
+    ) -> impl Future<Output = Result<ListPostResponse, Error>> + Send;
+
+    fn restore_trashed_post(
+        &self,
+        req: &TrashedPostRequest,
+    ) -> impl Future<Output = Result<Post, Error>> + Send;
+
+    fn delete_trashed_post(
+        &self,
+        req: &TrashedPostRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn purge_trash(
+        &self,
+        req: &PurgeTrashRequest,
+    ) -> impl Future<Output = Result<u64, Error>> + Send;
+
     fn create_user(
         &self,
         req: &CreateUserRequest,
@@ -110,7 +434,115 @@ pub trait BlogRepository: Clone + Send + Sync + 'static {
 
     fn get_user(&self, req: &GetUserRequest) -> impl Future<Output = Result<User, Error>> + Send;
 
-    fn login(&self, req: &LoginRequest) -> impl Future<Output = Result<String, Error>> + Send;
+    fn login(
+        &self,
+        req: &LoginRequest,
+    ) -> impl Future<Output = Result<LoginResponse, Error>> + Send;
+
+    fn verify_two_factor(
+        &self,
+        req: &VerifyTwoFactorRequest,
+    ) -> impl Future<Output = Result<TokenPair, Error>> + Send;
+
+    fn enroll_totp(
+        &self,
+        req: &EnrollTotpRequest,
+    ) -> impl Future<Output = Result<TotpEnrollment, Error>> + Send;
+
+    fn confirm_totp(
+        &self,
+        req: &ConfirmTotpRequest,
+    ) -> impl Future<Output = Result<Vec<String>, Error>> + Send;
+
+    fn disable_totp(
+        &self,
+        req: &DisableTotpRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn unlock_user(
+        &self,
+        req: &UnlockUserRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn refresh_token(
+        &self,
+        req: &RefreshTokenRequest,
+    ) -> impl Future<Output = Result<TokenPair, Error>> + Send;
+
+    fn logout(&self, req: &LogoutRequest) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn logout_all(&self, req: &LogoutAllRequest) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn is_token_revoked(&self, jti: &str) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    fn purge_expired_tokens(&self) -> impl Future<Output = Result<u64, Error>> + Send;
+
+    fn change_password(
+        &self,
+        req: &ChangePasswordRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn create_password_reset_tokens(
+        &self,
+        req: &ForgotPasswordRequest,
+    ) -> impl Future<Output = Result<Vec<PasswordResetToken>, Error>> + Send;
+
+    fn reset_password(
+        &self,
+        req: &ResetPasswordRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Issues a verification token for the user's email address, or `None`
+    /// when the user has no email or it is already verified.
+    fn create_email_verification_token(
+        &self,
+        user_id: &str,
+        expiration: u64,
+    ) -> impl Future<Output = Result<Option<EmailVerificationToken>, Error>> + Send;
+
+    fn verify_email(
+        &self,
+        req: &VerifyEmailRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn create_access_token(
+        &self,
+        req: &CreateAccessTokenRequest,
+    ) -> impl Future<Output = Result<NewAccessToken, Error>> + Send;
+
+    fn list_access_tokens(
+        &self,
+        req: &ListAccessTokensRequest,
+    ) -> impl Future<Output = Result<Vec<AccessToken>, Error>> + Send;
+
+    fn revoke_access_token(
+        &self,
+        req: &RevokeAccessTokenRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn authenticate_access_token(
+        &self,
+        token: &str,
+    ) -> impl Future<Output = Result<AccessToken, Error>> + Send;
+
+    fn save_oidc_login_state(
+        &self,
+        state: &OidcLoginState,
+        expiration: u64,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Consumes the state of a flow; unknown and expired states yield `None`.
+    fn take_oidc_login_state(
+        &self,
+        state: &str,
+    ) -> impl Future<Output = Result<Option<OidcLoginState>, Error>> + Send;
+
+    /// Finds the user linked to the identity, links an existing user with
+    /// the same verified email, or provisions a new one.
+    fn login_with_identity(
+        &self,
+        req: &IdentityLoginRequest,
+    ) -> impl Future<Output = Result<LoginResponse, Error>> + Send;
 
     fn check_permission(
         &self,
@@ -118,4 +550,68 @@ pub trait BlogRepository: Clone + Send + Sync + 'static {
         obj: &str,
         act: &str,
     ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    fn list_policies(&self) -> impl Future<Output = Result<Vec<Policy>, Error>> + Send;
+
+    /// Adds a `p` rule; an identical rule is a `Conflict`.
+    fn create_policy(
+        &self,
+        req: &PolicyRequest,
+    ) -> impl Future<Output = Result<Policy, Error>> + Send;
+
+    fn delete_policy(&self, req: &PolicyRequest) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn list_role_groupings(&self) -> impl Future<Output = Result<Vec<RoleGrouping>, Error>> + Send;
+
+    /// Adds a `g` rule; an identical rule is a `Conflict`.
+    fn create_role_grouping(
+        &self,
+        req: &RoleGroupingRequest,
+    ) -> impl Future<Output = Result<RoleGrouping, Error>> + Send;
+
+    fn delete_role_grouping(
+        &self,
+        req: &RoleGroupingRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn get_user_roles(
+        &self,
+        req: &GetUserRolesRequest,
+    ) -> impl Future<Output = Result<UserRoles, Error>> + Send;
+
+    /// Gives the user a role; assigning a role they already have is a no-op.
+    fn assign_role(
+        &self,
+        req: &RoleAssignmentRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    fn revoke_role(
+        &self,
+        req: &RoleAssignmentRequest,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Reports whether `sub` may do `act` on `obj`, and which rule allows it.
+    fn explain_permission(
+        &self,
+        req: &ExplainPermissionRequest,
+    ) -> impl Future<Output = Result<PermissionExplanation, Error>> + Send;
+}
+
+pub trait Mailer: Clone + Send + Sync + 'static {
+    fn send(&self, email: &Email) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+/// An OpenID Connect provider to sign users in with.
+pub trait IdentityProvider: Clone + Send + Sync + 'static {
+    fn authorization_url(
+        &self,
+        state: &OidcLoginState,
+    ) -> impl Future<Output = Result<String, Error>> + Send;
+
+    /// Redeems the authorization code and validates the returned ID token.
+    fn exchange_code(
+        &self,
+        code: &str,
+        state: &OidcLoginState,
+    ) -> impl Future<Output = Result<ExternalIdentity, Error>> + Send;
 }
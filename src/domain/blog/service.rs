@@ -1,38 +1,77 @@
 use super::{
     error::Error,
     models::{
+        access_tokens::{
+            AccessToken, CreateAccessTokenRequest, ListAccessTokensRequest, NewAccessToken,
+            RevokeAccessTokenRequest,
+        },
+        auth::{
+            ChangePasswordRequest, ForgotPasswordRequest, LoginResponse, LogoutAllRequest,
+            LogoutRequest, RefreshTokenRequest, ResetPasswordRequest, TokenPair, UnlockUserRequest,
+            VerifyEmailRequest,
+        },
+        mail::Email,
+        oidc::{IdentityLoginRequest, OidcCallbackRequest, OidcLoginState, StartOidcLoginRequest},
+        policies::{
+            ExplainPermissionRequest, GetUserRolesRequest, PermissionExplanation, Policy,
+            PolicyRequest, RoleAssignmentRequest, RoleGrouping, RoleGroupingRequest, UserRoles,
+        },
         posts::{
-            BatchDeletePostRequest, CreatePostRequest, DeletePostRequest, ListPostRequest,
-            ListPostResponse, Post, UpdatePostRequest,
+            BatchDeletePostRequest, ChangePostStatusRequest, CreatePostRequest, DeletePostRequest,
+            GetPostBySlugRequest, GetPostBySlugResponse, GetPostRequest, GetPublishedPostRequest,
+            ListPostRequest, ListPostResponse, ListTrashedPostRequest, Post, PurgeTrashRequest,
+            TrashedPostRequest, UpdatePostRequest,
+        },
+        revisions::{
+            DiffPostRevisionsRequest, GetPostRevisionRequest, ListPostRevisionsRequest,
+            PostRevision, PostRevisionDiff, RestorePostRevisionRequest,
+        },
+        search::{SearchPostRequest, SearchPostResponse},
+        tags::Tag,
+        two_factor::{
+            ConfirmTotpRequest, DisableTotpRequest, EnrollTotpRequest, TotpEnrollment,
+            VerifyTwoFactorRequest,
         },
         users::{
             CreateUserRequest, DeleteUserRequest, GetUserByIdRequest, GetUserRequest, LoginRequest,
             User,
         },
     },
-    ports::{BlogRepository, BlogService},
+    ports::{BlogRepository, BlogService, IdentityProvider, Mailer},
 };
 
 #[derive(Debug, Clone)]
-pub struct Service<R>
+pub struct Service<R, M, I>
 where
     R: BlogRepository,
+    M: Mailer,
+    I: IdentityProvider,
 {
     repo: R,
+    mailer: M,
+    identity_provider: I,
 }
 
-impl<R> Service<R>
+impl<R, M, I> Service<R, M, I>
 where
     R: BlogRepository,
+    M: Mailer,
+    I: IdentityProvider,
 {
-    pub fn new(repo: R) -> Self {
-        Self { repo }
+    pub fn new(repo: R, mailer: M, identity_provider: I) -> Self {
+        Self {
+            repo,
+            mailer,
+            identity_provider,
+        }
     }
 }
 
-impl<R> BlogService for Service<R>
+impl<R, M, I> BlogService for Service<R, M, I>
 where
     R: BlogRepository,
+    M: Mailer,
+    I: IdentityProvider,
 {
     async fn create_post(&self, req: &CreatePostRequest) -> Result<Post, Error> {
         self.repo.create_post(req).await
@@ -42,9 +81,79 @@ where
         self.repo.list_post(req).await
     }
 
+    async fn search_post(&self, req: &SearchPostRequest) -> Result<SearchPostResponse, Error> {
+        self.repo.search_post(req).await
+    }
+
+    async fn get_post(&self, req: &GetPostRequest) -> Result<Post, Error> {
+        self.repo.get_post(req).await
+    }
+
     async fn update_post(&self, req: &UpdatePostRequest) -> Result<Post, Error> {
         self.repo.update_post(req).await
     }
+
+    async fn change_post_status(&self, req: &ChangePostStatusRequest) -> Result<Post, Error> {
+        self.repo.change_post_status(req).await
+    }
+
+    async fn publish_due_posts(&self) -> Result<u64, Error> {
+        self.repo.publish_due_posts().await
+    }
+
+    async fn get_post_by_slug(
+        &self,
+        req: &GetPostBySlugRequest,
+    ) -> Result<GetPostBySlugResponse, Error> {
+        self.repo.get_post_by_slug(req).await
+    }
+
+    async fn get_published_post(&self, req: &GetPublishedPostRequest) -> Result<Post, Error> {
+        self.repo.get_published_post(req).await
+    }
+
+    async fn list_tags(&self) -> Result<Vec<Tag>, Error> {
+        self.repo.list_tags().await
+    }
+
+    async fn list_post_revisions(
+        &self,
+        req: &ListPostRevisionsRequest,
+    ) -> Result<Vec<PostRevision>, Error> {
+        self.repo.list_post_revisions(req).await
+    }
+
+    async fn get_post_revision(&self, req: &GetPostRevisionRequest) -> Result<PostRevision, Error> {
+        self.repo.get_post_revision(req).await
+    }
+
+    async fn diff_post_revisions(
+        &self,
+        req: &DiffPostRevisionsRequest,
+    ) -> Result<PostRevisionDiff, Error> {
+        let from = self
+            .repo
+            .get_post_revision(&GetPostRevisionRequest::new(
+                req.post_id.clone(),
+                req.username.clone(),
+                req.from,
+            )?)
+            .await?;
+        let to = self
+            .repo
+            .get_post_revision(&GetPostRevisionRequest::new(
+                req.post_id.clone(),
+                req.username.clone(),
+                req.to,
+            )?)
+            .await?;
+        Ok(PostRevisionDiff::new(from, to))
+    }
+
+    async fn restore_post_revision(&self, req: &RestorePostRevisionRequest) -> Result<Post, Error> {
+        self.repo.restore_post_revision(req).await
+    }
+
     async fn delete_post(&self, req: &DeletePostRequest) -> Result<(), Error> {
         self.repo.delete_post(req).await
     }
@@ -53,18 +162,125 @@ where
         self.repo.batch_delete_post(req).await
     }
 
+    async fn list_trashed_posts(
+        &self,
+        req: &ListTrashedPostRequest,
+    ) -> Result<ListPostResponse, Error> {
+        self.repo.list_trashed_posts(req).await
+    }
+
+    async fn restore_trashed_post(&self, req: &TrashedPostRequest) -> Result<Post, Error> {
+        self.repo.restore_trashed_post(req).await
+    }
+
+    async fn delete_trashed_post(&self, req: &TrashedPostRequest) -> Result<(), Error> {
+        self.repo.delete_trashed_post(req).await
+    }
+
+    async fn purge_trash(&self, req: &PurgeTrashRequest) -> Result<u64, Error> {
+        self.repo.purge_trash(req).await
+    }
+
     async fn create_user(&self, req: &CreateUserRequest) -> Result<User, Error> {
-        self.repo.create_user(req).await
+        let user = self.repo.create_user(req).await?;
+        let verification = self
+            .repo
+            .create_email_verification_token(&user.id, req.verification_expiration)
+            .await?;
+        if let Some(verification) = verification {
+            let email = Email::email_verification(
+                verification.email,
+                &verification.username,
+                &verification.token,
+                req.verification_expiration,
+            );
+            // The account exists either way; a lost email must not fail the
+            // registration.
+            if let Err(e) = self.mailer.send(&email).await {
+                tracing::error!("failed to send verification email: {:?}", e);
+            }
+        }
+        Ok(user)
     }
 
     async fn get_user(&self, req: &GetUserRequest) -> Result<User, Error> {
         self.repo.get_user(req).await
     }
 
-    async fn login(&self, req: &LoginRequest) -> Result<String, Error> {
+    async fn login(&self, req: &LoginRequest) -> Result<LoginResponse, Error> {
         self.repo.login(req).await
     }
 
+    async fn verify_two_factor(&self, req: &VerifyTwoFactorRequest) -> Result<TokenPair, Error> {
+        self.repo.verify_two_factor(req).await
+    }
+
+    async fn enroll_totp(&self, req: &EnrollTotpRequest) -> Result<TotpEnrollment, Error> {
+        self.repo.enroll_totp(req).await
+    }
+
+    async fn confirm_totp(&self, req: &ConfirmTotpRequest) -> Result<Vec<String>, Error> {
+        self.repo.confirm_totp(req).await
+    }
+
+    async fn disable_totp(&self, req: &DisableTotpRequest) -> Result<(), Error> {
+        self.repo.disable_totp(req).await
+    }
+
+    async fn unlock_user(&self, req: &UnlockUserRequest) -> Result<(), Error> {
+        self.repo.unlock_user(req).await
+    }
+
+    async fn refresh_token(&self, req: &RefreshTokenRequest) -> Result<TokenPair, Error> {
+        self.repo.refresh_token(req).await
+    }
+
+    async fn logout(&self, req: &LogoutRequest) -> Result<(), Error> {
+        self.repo.logout(req).await
+    }
+
+    async fn logout_all(&self, req: &LogoutAllRequest) -> Result<(), Error> {
+        self.repo.logout_all(req).await
+    }
+
+    async fn is_token_revoked(&self, jti: &str) -> Result<bool, Error> {
+        self.repo.is_token_revoked(jti).await
+    }
+
+    async fn purge_expired_tokens(&self) -> Result<u64, Error> {
+        self.repo.purge_expired_tokens().await
+    }
+
+    async fn change_password(&self, req: &ChangePasswordRequest) -> Result<(), Error> {
+        self.repo.change_password(req).await
+    }
+
+    async fn forgot_password(&self, req: &ForgotPasswordRequest) -> Result<(), Error> {
+        let tokens = self.repo.create_password_reset_tokens(req).await?;
+        // Mailing in the background keeps the response time, and any mail
+        // failure, the same whether or not the address has an account.
+        let mailer = self.mailer.clone();
+        let expiration = req.expiration;
+        tokio::spawn(async move {
+            for reset in tokens {
+                let email =
+                    Email::password_reset(reset.email, &reset.username, &reset.token, expiration);
+                if let Err(e) = mailer.send(&email).await {
+                    tracing::error!("failed to send password reset email: {:?}", e);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    async fn reset_password(&self, req: &ResetPasswordRequest) -> Result<(), Error> {
+        self.repo.reset_password(req).await
+    }
+
+    async fn verify_email(&self, req: &VerifyEmailRequest) -> Result<(), Error> {
+        self.repo.verify_email(req).await
+    }
+
     async fn delete_user(&self, req: &DeleteUserRequest) -> Result<(), Error> {
         self.repo.delete_user(req).await
     }
@@ -73,7 +289,104 @@ where
         self.repo.get_user_by_id(req).await
     }
 
+    async fn create_access_token(
+        &self,
+        req: &CreateAccessTokenRequest,
+    ) -> Result<NewAccessToken, Error> {
+        self.repo.create_access_token(req).await
+    }
+
+    async fn list_access_tokens(
+        &self,
+        req: &ListAccessTokensRequest,
+    ) -> Result<Vec<AccessToken>, Error> {
+        self.repo.list_access_tokens(req).await
+    }
+
+    async fn revoke_access_token(&self, req: &RevokeAccessTokenRequest) -> Result<(), Error> {
+        self.repo.revoke_access_token(req).await
+    }
+
+    async fn authenticate_access_token(&self, token: &str) -> Result<AccessToken, Error> {
+        self.repo.authenticate_access_token(token).await
+    }
+
+    async fn start_oidc_login(&self, req: &StartOidcLoginRequest) -> Result<String, Error> {
+        let state = OidcLoginState::generate();
+        let url = self.identity_provider.authorization_url(&state).await?;
+        self.repo
+            .save_oidc_login_state(&state, req.expiration)
+            .await?;
+        Ok(url)
+    }
+
+    async fn finish_oidc_login(&self, req: &OidcCallbackRequest) -> Result<LoginResponse, Error> {
+        let state = self
+            .repo
+            .take_oidc_login_state(&req.state)
+            .await?
+            .ok_or_else(|| Error::Unauthorized("invalid or expired login state".to_string()))?;
+        let identity = self
+            .identity_provider
+            .exchange_code(&req.code, &state)
+            .await?;
+        self.repo
+            .login_with_identity(&IdentityLoginRequest {
+                identity,
+                auto_provision: req.auto_provision,
+                default_role: req.default_role,
+                jwt: req.jwt.clone(),
+                expiration: req.expiration,
+                refresh_expiration: req.refresh_expiration,
+                policy: req.policy,
+            })
+            .await
+    }
+
     async fn check_permission(&self, sub: &str, obj: &str, act: &str) -> Result<bool, Error> {
         self.repo.check_permission(sub, obj, act).await
     }
+
+    async fn list_policies(&self) -> Result<Vec<Policy>, Error> {
+        self.repo.list_policies().await
+    }
+
+    async fn create_policy(&self, req: &PolicyRequest) -> Result<Policy, Error> {
+        self.repo.create_policy(req).await
+    }
+
+    async fn delete_policy(&self, req: &PolicyRequest) -> Result<(), Error> {
+        self.repo.delete_policy(req).await
+    }
+
+    async fn list_role_groupings(&self) -> Result<Vec<RoleGrouping>, Error> {
+        self.repo.list_role_groupings().await
+    }
+
+    async fn create_role_grouping(&self, req: &RoleGroupingRequest) -> Result<RoleGrouping, Error> {
+        self.repo.create_role_grouping(req).await
+    }
+
+    async fn delete_role_grouping(&self, req: &RoleGroupingRequest) -> Result<(), Error> {
+        self.repo.delete_role_grouping(req).await
+    }
+
+    async fn get_user_roles(&self, req: &GetUserRolesRequest) -> Result<UserRoles, Error> {
+        self.repo.get_user_roles(req).await
+    }
+
+    async fn assign_role(&self, req: &RoleAssignmentRequest) -> Result<(), Error> {
+        self.repo.assign_role(req).await
+    }
+
+    async fn revoke_role(&self, req: &RoleAssignmentRequest) -> Result<(), Error> {
+        self.repo.revoke_role(req).await
+    }
+
+    async fn explain_permission(
+        &self,
+        req: &ExplainPermissionRequest,
+    ) -> Result<PermissionExplanation, Error> {
+        self.repo.explain_permission(req).await
+    }
 }
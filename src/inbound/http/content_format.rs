@@ -0,0 +1,31 @@
+use serde::Deserialize;
+
+use crate::domain::blog::models::posts::Post;
+
+/// Which forms of a post's content a response carries, selected with the
+/// `format` query parameter.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFormat {
+    #[default]
+    Markdown,
+    Html,
+    Both,
+}
+
+impl ContentFormat {
+    /// Returns the Markdown and HTML content of `post` to put in a response.
+    pub fn select(&self, post: &Post) -> (Option<String>, Option<String>) {
+        match self {
+            ContentFormat::Markdown => (Some(post.content.clone()), None),
+            ContentFormat::Html => (None, Some(post.html().into_owned())),
+            ContentFormat::Both => (Some(post.content.clone()), Some(post.html().into_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ContentFormatQuery {
+    #[serde(default)]
+    pub format: ContentFormat,
+}
@@ -0,0 +1,25 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+use crate::{
+    domain::blog::{models::policies::RoleAssignmentRequest, ports::BlogService},
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+pub async fn assign_role<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Path((username, role)): Path<(String, String)>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let domain_req = RoleAssignmentRequest::new(username, &role)?;
+    state
+        .blog_service
+        .assign_role(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
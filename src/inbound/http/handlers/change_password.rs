@@ -0,0 +1,45 @@
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{auth::ChangePasswordRequest, users::User},
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ChangePasswordHttpRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+impl ChangePasswordHttpRequest {
+    pub fn try_into_domain(self, user_id: &str) -> Result<ChangePasswordRequest, Error> {
+        let req = ChangePasswordRequest::new(
+            user_id.to_string(),
+            self.current_password,
+            self.new_password,
+        )?;
+        Ok(req)
+    }
+}
+
+pub async fn change_password<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Json(body): Json<ChangePasswordHttpRequest>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let domain_req = body.try_into_domain(&user.id)?;
+    state
+        .blog_service
+        .change_password(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
@@ -0,0 +1,51 @@
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{two_factor::ConfirmTotpRequest, users::User},
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ConfirmTotpHttpRequest {
+    pub code: String,
+}
+
+impl ConfirmTotpHttpRequest {
+    pub fn try_into_domain(
+        self,
+        user_id: &str,
+        recovery_codes: usize,
+    ) -> Result<ConfirmTotpRequest, Error> {
+        let req = ConfirmTotpRequest::new(user_id.to_string(), self.code, recovery_codes)?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfirmTotpResponseData {
+    pub recovery_codes: Vec<String>,
+}
+
+pub async fn confirm_totp<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Json(body): Json<ConfirmTotpHttpRequest>,
+) -> Result<ApiSuccess<ConfirmTotpResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(&user.id, state.config.auth.two_factor.recovery_codes)?;
+    state
+        .blog_service
+        .confirm_totp(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|recovery_codes| {
+            ApiSuccess::new(StatusCode::OK, ConfirmTotpResponseData { recovery_codes })
+        })
+}
@@ -0,0 +1,91 @@
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{
+            access_tokens::{AccessToken, CreateAccessTokenRequest, NewAccessToken, TokenScope},
+            users::User,
+        },
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CreateAccessTokenHttpRequest {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl CreateAccessTokenHttpRequest {
+    pub fn try_into_domain(self, user_id: &str) -> Result<CreateAccessTokenRequest, Error> {
+        let req = CreateAccessTokenRequest::new(
+            user_id.to_string(),
+            self.name,
+            self.scopes,
+            self.expires_at,
+        )?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AccessTokenInfo {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&AccessToken> for AccessTokenInfo {
+    fn from(token: &AccessToken) -> Self {
+        Self {
+            id: token.id.clone(),
+            name: token.name.clone(),
+            scopes: token.scopes.clone(),
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CreateAccessTokenResponseData {
+    #[serde(flatten)]
+    pub info: AccessTokenInfo,
+    /// The secret itself; it cannot be retrieved again.
+    pub token: String,
+}
+
+impl From<&NewAccessToken> for CreateAccessTokenResponseData {
+    fn from(new_token: &NewAccessToken) -> Self {
+        Self {
+            info: (&new_token.token).into(),
+            token: new_token.secret.clone(),
+        }
+    }
+}
+
+pub async fn create_access_token<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Json(body): Json<CreateAccessTokenHttpRequest>,
+) -> Result<ApiSuccess<CreateAccessTokenResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(&user.id)?;
+    state
+        .blog_service
+        .create_access_token(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref new_token| ApiSuccess::new(StatusCode::CREATED, new_token.into()))
+}
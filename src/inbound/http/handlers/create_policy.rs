@@ -0,0 +1,38 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{error::Error, models::policies::PolicyRequest, ports::BlogService},
+    inbound::http::{
+        handlers::list_policies::PolicyInfo,
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PolicyHttpRequest {
+    pub sub: String,
+    pub obj: String,
+    pub act: String,
+}
+
+impl PolicyHttpRequest {
+    pub fn try_into_domain(self) -> Result<PolicyRequest, Error> {
+        let req = PolicyRequest::new(self.sub, self.obj, self.act)?;
+        Ok(req)
+    }
+}
+
+pub async fn create_policy<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Json(body): Json<PolicyHttpRequest>,
+) -> Result<ApiSuccess<PolicyInfo>, ApiError> {
+    let domain_req = body.try_into_domain()?;
+    state
+        .blog_service
+        .create_policy(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref policy| ApiSuccess::new(StatusCode::CREATED, policy.into()))
+}
@@ -4,10 +4,11 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    config::VerificationRequirement,
     domain::blog::{
         error::Error,
         models::{
-            posts::{CreatePostRequest, Post},
+            posts::{CreatePostRequest, Post, PostStatus},
             users::User,
         },
         ports::BlogService,
@@ -22,11 +23,20 @@ use crate::{
 pub struct CreatePostHttpRequestBody {
     pub title: String,
     pub content: String,
+    pub publish_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl CreatePostHttpRequestBody {
     fn try_into_domain(self, username: &str) -> Result<CreatePostRequest, Error> {
-        let req = CreatePostRequest::new(self.title, self.content, username.to_string())?;
+        let req = CreatePostRequest::new(
+            self.title,
+            self.content,
+            username.to_string(),
+            self.publish_at,
+            self.tags,
+        )?;
         Ok(req)
     }
 }
@@ -35,8 +45,13 @@ impl CreatePostHttpRequestBody {
 pub struct CreatePostResponseData {
     pub id: String,
     pub title: String,
+    pub slug: String,
     pub content: String,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub publish_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    pub tags: Vec<String>,
 }
 
 impl From<&Post> for CreatePostResponseData {
@@ -44,8 +59,13 @@ impl From<&Post> for CreatePostResponseData {
         Self {
             id: post.id.to_string(),
             title: post.title.clone(),
+            slug: post.slug.clone(),
             content: post.content.clone(),
+            status: post.status,
+            published_at: post.published_at,
+            publish_at: post.publish_at,
             created_at: post.created_at,
+            tags: post.tags.clone(),
         }
     }
 }
@@ -55,6 +75,13 @@ pub async fn create_post<BS: BlogService>(
     State(state): State<AppState<BS>>,
     Json(body): Json<CreatePostHttpRequestBody>,
 ) -> Result<ApiSuccess<CreatePostResponseData>, ApiError> {
+    if state.config.auth.email_verification.require_for == VerificationRequirement::CreatePost
+        && !user.is_email_verified()
+    {
+        return Err(ApiError::PermissionDenied(
+            "email address is not verified".to_string(),
+        ));
+    }
     let domain_req = body.try_into_domain(&user.username)?;
     state
         .blog_service
@@ -0,0 +1,37 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{error::Error, models::policies::RoleGroupingRequest, ports::BlogService},
+    inbound::http::{
+        handlers::list_role_groupings::RoleGroupingInfo,
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RoleGroupingHttpRequest {
+    pub subject: String,
+    pub role: String,
+}
+
+impl RoleGroupingHttpRequest {
+    pub fn try_into_domain(self) -> Result<RoleGroupingRequest, Error> {
+        let req = RoleGroupingRequest::new(self.subject, self.role)?;
+        Ok(req)
+    }
+}
+
+pub async fn create_role_grouping<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Json(body): Json<RoleGroupingHttpRequest>,
+) -> Result<ApiSuccess<RoleGroupingInfo>, ApiError> {
+    let domain_req = body.try_into_domain()?;
+    state
+        .blog_service
+        .create_role_grouping(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref grouping| ApiSuccess::new(StatusCode::CREATED, grouping.into()))
+}
@@ -3,9 +3,13 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    config::VerificationRequirement,
     domain::blog::{
         error::Error,
-        models::users::{CreateUserRequest, User},
+        models::{
+            roles::Role,
+            users::{CreateUserRequest, User},
+        },
         ports::BlogService,
     },
     inbound::http::{
@@ -23,8 +27,21 @@ pub struct CreateUserHttpRequest {
 }
 
 impl CreateUserHttpRequest {
-    pub fn try_into_domain(self) -> Result<CreateUserRequest, Error> {
-        let req = CreateUserRequest::new(self.username, self.email, self.phone, self.password)?;
+    pub fn try_into_domain(
+        self,
+        email_required: bool,
+        verification_expiration: u64,
+        role: Role,
+    ) -> Result<CreateUserRequest, Error> {
+        let req = CreateUserRequest::new(
+            self.username,
+            self.email,
+            self.phone,
+            self.password,
+            email_required,
+            verification_expiration,
+            role,
+        )?;
         Ok(req)
     }
 }
@@ -35,6 +52,7 @@ pub struct CreateUserResponseData {
     pub username: String,
     pub email: Option<String>,
     pub phone: Option<String>,
+    pub email_verified: bool,
     pub created_at: DateTime<Utc>,
 }
 
@@ -45,6 +63,7 @@ impl From<&User> for CreateUserResponseData {
             username: user.username.clone(),
             email: user.email.clone(),
             phone: user.phone.clone(),
+            email_verified: user.is_email_verified(),
             created_at: user.created_at,
         }
     }
@@ -54,7 +73,13 @@ pub async fn create_user<BS: BlogService>(
     State(state): State<AppState<BS>>,
     Json(body): Json<CreateUserHttpRequest>,
 ) -> Result<ApiSuccess<CreateUserResponseData>, ApiError> {
-    let domain_req = body.try_into_domain()?;
+    let verification = &state.config.auth.email_verification;
+    let email_required = verification.require_for != VerificationRequirement::Nothing;
+    let domain_req = body.try_into_domain(
+        email_required,
+        verification.expiration,
+        state.config.auth.default_role,
+    )?;
     state
         .blog_service
         .create_user(&domain_req)
@@ -0,0 +1,23 @@
+use axum::{extract::State, http::StatusCode, Json};
+
+use crate::{
+    domain::blog::ports::BlogService,
+    inbound::http::{
+        handlers::create_policy::PolicyHttpRequest,
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+pub async fn delete_policy<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Json(body): Json<PolicyHttpRequest>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let domain_req = body.try_into_domain()?;
+    state
+        .blog_service
+        .delete_policy(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
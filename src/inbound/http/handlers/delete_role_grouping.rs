@@ -0,0 +1,23 @@
+use axum::{extract::State, http::StatusCode, Json};
+
+use crate::{
+    domain::blog::ports::BlogService,
+    inbound::http::{
+        handlers::create_role_grouping::RoleGroupingHttpRequest,
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+pub async fn delete_role_grouping<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Json(body): Json<RoleGroupingHttpRequest>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let domain_req = body.try_into_domain()?;
+    state
+        .blog_service
+        .delete_role_grouping(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
@@ -0,0 +1,30 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+
+use crate::{
+    domain::blog::{
+        models::{posts::TrashedPostRequest, users::User},
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+pub async fn delete_trashed_post<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let domain_req = TrashedPostRequest::new(id, user.username)?;
+    state
+        .blog_service
+        .delete_trashed_post(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{
+            revisions::{DiffPostRevisionsRequest, PostRevisionDiff},
+            users::User,
+        },
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiffPostRevisionsHttpRequestBody {
+    pub from: i32,
+    pub to: i32,
+}
+
+impl DiffPostRevisionsHttpRequestBody {
+    fn try_into_domain(
+        self,
+        id: String,
+        username: &str,
+    ) -> Result<DiffPostRevisionsRequest, Error> {
+        let req = DiffPostRevisionsRequest::new(id, username.to_string(), self.from, self.to)?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct DiffPostRevisionsResponseData {
+    pub from: i32,
+    pub to: i32,
+    pub from_title: String,
+    pub to_title: String,
+    pub diff: String,
+}
+
+impl From<&PostRevisionDiff> for DiffPostRevisionsResponseData {
+    fn from(diff: &PostRevisionDiff) -> Self {
+        Self {
+            from: diff.from.revision,
+            to: diff.to.revision,
+            from_title: diff.from.title.clone(),
+            to_title: diff.to.title.clone(),
+            diff: diff.diff.clone(),
+        }
+    }
+}
+
+pub async fn diff_post_revisions<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Path(id): Path<String>,
+    Query(body): Query<DiffPostRevisionsHttpRequestBody>,
+) -> Result<ApiSuccess<DiffPostRevisionsResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(id, &user.username)?;
+    state
+        .blog_service
+        .diff_post_revisions(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref diff| ApiSuccess::new(StatusCode::OK, diff.into()))
+}
@@ -0,0 +1,46 @@
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{auth::LoginThrottle, two_factor::DisableTotpRequest, users::User},
+        ports::BlogService,
+    },
+    inbound::http::{
+        handlers::login::login_throttle,
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DisableTotpHttpRequest {
+    pub code: String,
+}
+
+impl DisableTotpHttpRequest {
+    pub fn try_into_domain(
+        self,
+        user_id: &str,
+        throttle: LoginThrottle,
+    ) -> Result<DisableTotpRequest, Error> {
+        let req = DisableTotpRequest::new(user_id.to_string(), self.code, throttle)?;
+        Ok(req)
+    }
+}
+
+pub async fn disable_totp<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Json(body): Json<DisableTotpHttpRequest>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let throttle = login_throttle(&state.config.auth.login_throttle);
+    let domain_req = body.try_into_domain(&user.id, throttle)?;
+    state
+        .blog_service
+        .disable_totp(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
@@ -0,0 +1,44 @@
+use axum::{extract::State, http::StatusCode, Extension};
+use serde::Serialize;
+
+use crate::{
+    domain::blog::{
+        models::{
+            two_factor::{EnrollTotpRequest, TotpEnrollment},
+            users::User,
+        },
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EnrollTotpResponseData {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+impl From<&TotpEnrollment> for EnrollTotpResponseData {
+    fn from(enrollment: &TotpEnrollment) -> Self {
+        Self {
+            secret: enrollment.secret.clone(),
+            otpauth_uri: enrollment.otpauth_uri.clone(),
+        }
+    }
+}
+
+pub async fn enroll_totp<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+) -> Result<ApiSuccess<EnrollTotpResponseData>, ApiError> {
+    let domain_req = EnrollTotpRequest::new(user.id, state.config.auth.two_factor.issuer.clone())?;
+    state
+        .blog_service
+        .enroll_totp(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref enrollment| ApiSuccess::new(StatusCode::OK, enrollment.into()))
+}
@@ -0,0 +1,64 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::policies::{ExplainPermissionRequest, PermissionExplanation},
+        ports::BlogService,
+    },
+    inbound::http::{
+        handlers::list_policies::PolicyInfo,
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ExplainPermissionHttpRequest {
+    pub sub: String,
+    pub obj: String,
+    pub act: String,
+}
+
+impl ExplainPermissionHttpRequest {
+    pub fn try_into_domain(self) -> Result<ExplainPermissionRequest, Error> {
+        let req = ExplainPermissionRequest::new(self.sub, self.obj, self.act)?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ExplainPermissionResponseData {
+    pub allowed: bool,
+    /// The rule granting the request; `null` when denied.
+    pub policy: Option<PolicyInfo>,
+    /// Every role `sub` holds, directly or inherited.
+    pub roles: Vec<String>,
+}
+
+impl From<&PermissionExplanation> for ExplainPermissionResponseData {
+    fn from(explanation: &PermissionExplanation) -> Self {
+        Self {
+            allowed: explanation.allowed,
+            policy: explanation.policy.as_ref().map(PolicyInfo::from),
+            roles: explanation.roles.clone(),
+        }
+    }
+}
+
+pub async fn explain_permission<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Query(query): Query<ExplainPermissionHttpRequest>,
+) -> Result<ApiSuccess<ExplainPermissionResponseData>, ApiError> {
+    let domain_req = query.try_into_domain()?;
+    state
+        .blog_service
+        .explain_permission(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref explanation| ApiSuccess::new(StatusCode::OK, explanation.into()))
+}
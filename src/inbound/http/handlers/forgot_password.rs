@@ -0,0 +1,36 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{error::Error, models::auth::ForgotPasswordRequest, ports::BlogService},
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ForgotPasswordHttpRequest {
+    pub email: String,
+}
+
+impl ForgotPasswordHttpRequest {
+    pub fn try_into_domain(self, expiration: u64) -> Result<ForgotPasswordRequest, Error> {
+        let req = ForgotPasswordRequest::new(self.email, expiration)?;
+        Ok(req)
+    }
+}
+
+pub async fn forgot_password<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Json(body): Json<ForgotPasswordHttpRequest>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let expiration = state.config.auth.password_reset_expiration;
+    let domain_req = body.try_into_domain(expiration)?;
+    state
+        .blog_service
+        .forgot_password(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::ACCEPTED, ()))
+}
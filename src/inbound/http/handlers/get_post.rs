@@ -0,0 +1,88 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{
+            posts::{GetPostRequest, Post, PostStatus},
+            users::User,
+        },
+        ports::BlogService,
+    },
+    inbound::http::{
+        content_format::{ContentFormat, ContentFormatQuery},
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GetPostHttpRequest {
+    pub id: String,
+}
+
+impl GetPostHttpRequest {
+    pub fn try_into_domain(self, username: &str) -> Result<GetPostRequest, Error> {
+        let req = GetPostRequest::new(self.id, username.to_string())?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GetPostResponseData {
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_html: Option<String>,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub tags: Vec<String>,
+}
+
+impl GetPostResponseData {
+    pub fn new(post: &Post, format: ContentFormat) -> Self {
+        let (content, content_html) = format.select(post);
+        Self {
+            id: post.id.to_string(),
+            title: post.title.clone(),
+            slug: post.slug.clone(),
+            content,
+            content_html,
+            status: post.status,
+            published_at: post.published_at,
+            publish_at: post.publish_at,
+            created_at: post.created_at,
+            updated_at: post.updated_at,
+            tags: post.tags.clone(),
+        }
+    }
+}
+
+pub async fn get_post<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Path(body): Path<GetPostHttpRequest>,
+    Query(query): Query<ContentFormatQuery>,
+) -> Result<ApiSuccess<GetPostResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(&user.username)?;
+    state
+        .blog_service
+        .get_post(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref post| {
+            ApiSuccess::new(StatusCode::OK, GetPostResponseData::new(post, query.format))
+        })
+}
@@ -0,0 +1,127 @@
+use axum::{
+    extract::{OriginalUri, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use chrono::{DateTime, Utc};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{
+            posts::{GetPostBySlugRequest, GetPostBySlugResponse, Post, PostStatus},
+            users::User,
+        },
+        ports::BlogService,
+    },
+    inbound::http::{
+        content_format::{ContentFormat, ContentFormatQuery},
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_');
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GetPostBySlugHttpRequest {
+    pub username: String,
+    pub slug: String,
+}
+
+impl GetPostBySlugHttpRequest {
+    pub fn try_into_domain(self, viewer: &str) -> Result<GetPostBySlugRequest, Error> {
+        let req = GetPostBySlugRequest::new(self.username, self.slug, viewer.to_string())?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GetPostBySlugResponseData {
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_html: Option<String>,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub tags: Vec<String>,
+}
+
+impl GetPostBySlugResponseData {
+    pub fn new(post: &Post, format: ContentFormat) -> Self {
+        let (content, content_html) = format.select(post);
+        Self {
+            id: post.id.to_string(),
+            title: post.title.clone(),
+            slug: post.slug.clone(),
+            content,
+            content_html,
+            status: post.status,
+            published_at: post.published_at,
+            publish_at: post.publish_at,
+            created_at: post.created_at,
+            updated_at: post.updated_at,
+            tags: post.tags.clone(),
+        }
+    }
+}
+
+pub async fn get_post_by_slug<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    OriginalUri(uri): OriginalUri,
+    Path(body): Path<GetPostBySlugHttpRequest>,
+    Query(query): Query<ContentFormatQuery>,
+) -> Result<Response, ApiError> {
+    let req = body.try_into_domain(&user.username)?;
+    let res = state
+        .blog_service
+        .get_post_by_slug(&req)
+        .await
+        .map_err(ApiError::from)?;
+    match res {
+        GetPostBySlugResponse::Found(ref post) => {
+            let data = GetPostBySlugResponseData::new(post, query.format);
+            Ok(ApiSuccess::new(StatusCode::OK, data).into_response())
+        }
+        GetPostBySlugResponse::Moved { username, slug } => Ok(moved_permanently(
+            "/api/posts/by-slug",
+            &username,
+            &slug,
+            uri.query(),
+        )),
+    }
+}
+
+/// Redirects a request for an old slug to the post's current slug under
+/// `prefix`, keeping the original query string.
+pub fn moved_permanently(
+    prefix: &str,
+    username: &str,
+    slug: &str,
+    query: Option<&str>,
+) -> Response {
+    let mut location = format!(
+        "{}/{}/{}",
+        prefix,
+        utf8_percent_encode(username, PATH_SEGMENT),
+        utf8_percent_encode(slug, PATH_SEGMENT)
+    );
+    if let Some(query) = query {
+        location = format!("{location}?{query}");
+    }
+    (
+        StatusCode::MOVED_PERMANENTLY,
+        [(header::LOCATION, location)],
+    )
+        .into_response()
+}
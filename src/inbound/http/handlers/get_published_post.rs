@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::posts::{GetPublishedPostRequest, Post},
+        ports::BlogService,
+    },
+    inbound::http::{
+        content_format::{ContentFormat, ContentFormatQuery},
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GetPublishedPostHttpRequest {
+    pub id: String,
+}
+
+impl GetPublishedPostHttpRequest {
+    pub fn try_into_domain(self) -> Result<GetPublishedPostRequest, Error> {
+        let req = GetPublishedPostRequest::new(self.id)?;
+        Ok(req)
+    }
+}
+
+/// A post as shown to anonymous readers, without any of the scheduling or
+/// lifecycle fields only its author cares about.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PublishedPostResponseData {
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_html: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+    pub tags: Vec<String>,
+}
+
+impl PublishedPostResponseData {
+    pub fn new(post: &Post, format: ContentFormat) -> Self {
+        let (content, content_html) = format.select(post);
+        Self {
+            id: post.id.to_string(),
+            title: post.title.clone(),
+            slug: post.slug.clone(),
+            content,
+            content_html,
+            published_at: post.published_at,
+            updated_at: post.updated_at,
+            tags: post.tags.clone(),
+        }
+    }
+}
+
+pub async fn get_published_post<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Path(body): Path<GetPublishedPostHttpRequest>,
+    Query(query): Query<ContentFormatQuery>,
+) -> Result<ApiSuccess<PublishedPostResponseData>, ApiError> {
+    let req = body.try_into_domain()?;
+    state
+        .blog_service
+        .get_published_post(&req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref post| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                PublishedPostResponseData::new(post, query.format),
+            )
+        })
+}
@@ -0,0 +1,61 @@
+use axum::{
+    extract::{OriginalUri, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::posts::{GetPostBySlugRequest, GetPostBySlugResponse},
+        ports::BlogService,
+    },
+    inbound::http::{
+        content_format::ContentFormatQuery,
+        handlers::{
+            get_post_by_slug::moved_permanently, get_published_post::PublishedPostResponseData,
+        },
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GetPublishedPostBySlugHttpRequest {
+    pub username: String,
+    pub slug: String,
+}
+
+impl GetPublishedPostBySlugHttpRequest {
+    pub fn try_into_domain(self) -> Result<GetPostBySlugRequest, Error> {
+        let req = GetPostBySlugRequest::published(self.username, self.slug)?;
+        Ok(req)
+    }
+}
+
+pub async fn get_published_post_by_slug<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    OriginalUri(uri): OriginalUri,
+    Path(body): Path<GetPublishedPostBySlugHttpRequest>,
+    Query(query): Query<ContentFormatQuery>,
+) -> Result<Response, ApiError> {
+    let req = body.try_into_domain()?;
+    let res = state
+        .blog_service
+        .get_post_by_slug(&req)
+        .await
+        .map_err(ApiError::from)?;
+    match res {
+        GetPostBySlugResponse::Found(ref post) => {
+            let data = PublishedPostResponseData::new(post, query.format);
+            Ok(ApiSuccess::new(StatusCode::OK, data).into_response())
+        }
+        GetPostBySlugResponse::Moved { username, slug } => Ok(moved_permanently(
+            "/api/public/posts/by-slug",
+            &username,
+            &slug,
+            uri.query(),
+        )),
+    }
+}
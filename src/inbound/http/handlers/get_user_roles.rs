@@ -0,0 +1,46 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::Serialize;
+
+use crate::{
+    domain::blog::{
+        models::policies::{GetUserRolesRequest, UserRoles},
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct UserRolesResponseData {
+    pub username: String,
+    pub roles: Vec<String>,
+    pub implicit_roles: Vec<String>,
+}
+
+impl From<&UserRoles> for UserRolesResponseData {
+    fn from(roles: &UserRoles) -> Self {
+        Self {
+            username: roles.username.clone(),
+            roles: roles.roles.clone(),
+            implicit_roles: roles.implicit_roles.clone(),
+        }
+    }
+}
+
+pub async fn get_user_roles<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Path(username): Path<String>,
+) -> Result<ApiSuccess<UserRolesResponseData>, ApiError> {
+    let domain_req = GetUserRolesRequest::new(username)?;
+    state
+        .blog_service
+        .get_user_roles(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref roles| ApiSuccess::new(StatusCode::OK, roles.into()))
+}
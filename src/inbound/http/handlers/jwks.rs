@@ -0,0 +1,11 @@
+use axum::{extract::State, Json};
+
+use crate::{
+    domain::blog::ports::BlogService, inbound::http::http_server::AppState, utils::jwks::JwkSet,
+};
+
+/// Serves the public verification keys as a bare JWK set, the format other
+/// services' JWT libraries expect, rather than inside the API envelope.
+pub async fn jwks<BS: BlogService>(State(state): State<AppState<BS>>) -> Json<JwkSet> {
+    Json(state.jwt.jwks().clone())
+}
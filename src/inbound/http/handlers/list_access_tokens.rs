@@ -0,0 +1,39 @@
+use axum::{extract::State, http::StatusCode, Extension};
+use serde::Serialize;
+
+use crate::{
+    domain::blog::{
+        models::{access_tokens::ListAccessTokensRequest, users::User},
+        ports::BlogService,
+    },
+    inbound::http::{
+        handlers::create_access_token::AccessTokenInfo,
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ListAccessTokensResponseData {
+    pub tokens: Vec<AccessTokenInfo>,
+}
+
+pub async fn list_access_tokens<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+) -> Result<ApiSuccess<ListAccessTokensResponseData>, ApiError> {
+    let domain_req = ListAccessTokensRequest::new(user.id)?;
+    state
+        .blog_service
+        .list_access_tokens(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|tokens| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListAccessTokensResponseData {
+                    tokens: tokens.iter().map(AccessTokenInfo::from).collect(),
+                },
+            )
+        })
+}
@@ -0,0 +1,50 @@
+use axum::{extract::State, http::StatusCode};
+use serde::Serialize;
+
+use crate::{
+    domain::blog::{models::policies::Policy, ports::BlogService},
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct PolicyInfo {
+    pub sub: String,
+    pub obj: String,
+    pub act: String,
+}
+
+impl From<&Policy> for PolicyInfo {
+    fn from(policy: &Policy) -> Self {
+        Self {
+            sub: policy.sub.clone(),
+            obj: policy.obj.clone(),
+            act: policy.act.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ListPoliciesHttpResponseBody {
+    pub policies: Vec<PolicyInfo>,
+}
+
+pub async fn list_policies<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+) -> Result<ApiSuccess<ListPoliciesHttpResponseBody>, ApiError> {
+    state
+        .blog_service
+        .list_policies()
+        .await
+        .map_err(ApiError::from)
+        .map(|ref policies| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListPoliciesHttpResponseBody {
+                    policies: policies.iter().map(PolicyInfo::from).collect(),
+                },
+            )
+        })
+}
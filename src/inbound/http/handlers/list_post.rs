@@ -10,12 +10,13 @@ use crate::{
     domain::blog::{
         error::Error,
         models::{
-            posts::{ListPostRequest, ListPostResponse, Post},
+            posts::{ListPostRequest, ListPostResponse, Post, PostStatus, TagMatch},
             users::User,
         },
         ports::BlogService,
     },
     inbound::http::{
+        content_format::ContentFormat,
         http_server::AppState,
         response::{ApiError, ApiSuccess},
     },
@@ -25,11 +26,34 @@ use crate::{
 pub struct ListPostHttpRequestBody {
     pub offset: u32,
     pub limit: u32,
+    pub username: Option<String>,
+    pub status: Option<PostStatus>,
+    /// Comma separated list of tags.
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub tag_match: TagMatch,
+    #[serde(default)]
+    pub format: ContentFormat,
 }
 
 impl ListPostHttpRequestBody {
     fn try_into_domain(self, username: &str) -> Result<ListPostRequest, Error> {
-        let req = ListPostRequest::new(self.offset, self.limit, username.to_string())?;
+        let req = match self.username {
+            Some(author) if author != username => {
+                if self.status.is_some_and(|s| s != PostStatus::Published) {
+                    return Err(Error::Forbidden(
+                        "only published posts of other users can be listed".to_string(),
+                    ));
+                }
+                ListPostRequest::published(self.offset, self.limit, author)?
+            }
+            _ => ListPostRequest::new(self.offset, self.limit, username.to_string(), self.status)?,
+        };
+        let tags = self
+            .tag
+            .map(|tag| tag.split(',').map(str::to_string).collect())
+            .unwrap_or_default();
+        let req = req.with_tags(tags, self.tag_match)?;
         Ok(req)
     }
 }
@@ -38,19 +62,34 @@ impl ListPostHttpRequestBody {
 pub struct PostInfo {
     pub post_id: String,
     pub title: String,
-    pub content: String,
+    pub slug: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_html: Option<String>,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub publish_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub tags: Vec<String>,
 }
 
-impl From<&Post> for PostInfo {
-    fn from(post: &Post) -> Self {
+impl PostInfo {
+    pub fn new(post: &Post, format: ContentFormat) -> Self {
+        let (content, content_html) = format.select(post);
         Self {
             post_id: post.id.to_string(),
             title: post.title.clone(),
-            content: post.content.clone(),
+            slug: post.slug.clone(),
+            content,
+            content_html,
+            status: post.status,
+            published_at: post.published_at,
+            publish_at: post.publish_at,
             created_at: post.created_at,
             updated_at: post.updated_at,
+            tags: post.tags.clone(),
         }
     }
 }
@@ -61,11 +100,15 @@ pub struct ListPostHttpResponseBody {
     pub posts: Vec<PostInfo>,
 }
 
-impl From<&ListPostResponse> for ListPostHttpResponseBody {
-    fn from(res: &ListPostResponse) -> Self {
+impl ListPostHttpResponseBody {
+    pub fn new(res: &ListPostResponse, format: ContentFormat) -> Self {
         Self {
             total: res.total,
-            posts: res.posts.iter().map(PostInfo::from).collect(),
+            posts: res
+                .posts
+                .iter()
+                .map(|post| PostInfo::new(post, format))
+                .collect(),
         }
     }
 }
@@ -75,11 +118,12 @@ pub async fn list_post<BS: BlogService>(
     State(state): State<AppState<BS>>,
     Query(body): Query<ListPostHttpRequestBody>,
 ) -> Result<ApiSuccess<ListPostHttpResponseBody>, ApiError> {
+    let format = body.format;
     let domain_req = body.try_into_domain(&user.username)?;
     state
         .blog_service
         .list_post(domain_req)
         .await
         .map_err(ApiError::from)
-        .map(|ref res| ApiSuccess::new(StatusCode::OK, res.into()))
+        .map(|ref res| ApiSuccess::new(StatusCode::OK, ListPostHttpResponseBody::new(res, format)))
 }
@@ -0,0 +1,64 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{
+    domain::blog::{
+        models::{
+            revisions::{ListPostRevisionsRequest, PostRevision},
+            users::User,
+        },
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct PostRevisionInfo {
+    pub revision: i32,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&PostRevision> for PostRevisionInfo {
+    fn from(revision: &PostRevision) -> Self {
+        Self {
+            revision: revision.revision,
+            title: revision.title.clone(),
+            created_at: revision.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ListPostRevisionsHttpResponseBody {
+    pub revisions: Vec<PostRevisionInfo>,
+}
+
+pub async fn list_post_revisions<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<ListPostRevisionsHttpResponseBody>, ApiError> {
+    let domain_req = ListPostRevisionsRequest::new(id, user.username)?;
+    state
+        .blog_service
+        .list_post_revisions(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref revisions| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListPostRevisionsHttpResponseBody {
+                    revisions: revisions.iter().map(PostRevisionInfo::from).collect(),
+                },
+            )
+        })
+}
@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::posts::{ListPostRequest, ListPostResponse, TagMatch},
+        ports::BlogService,
+    },
+    inbound::http::{
+        content_format::ContentFormat,
+        handlers::get_published_post::PublishedPostResponseData,
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListPublishedPostsHttpRequestBody {
+    pub offset: u32,
+    pub limit: u32,
+    /// Comma separated list of tags.
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub tag_match: TagMatch,
+    #[serde(default)]
+    pub format: ContentFormat,
+}
+
+impl ListPublishedPostsHttpRequestBody {
+    fn try_into_domain(self, username: String) -> Result<ListPostRequest, Error> {
+        let tags = self
+            .tag
+            .map(|tag| tag.split(',').map(str::to_string).collect())
+            .unwrap_or_default();
+        let req = ListPostRequest::published(self.offset, self.limit, username)?
+            .with_tags(tags, self.tag_match)?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ListPublishedPostsHttpResponseBody {
+    pub total: u64,
+    pub posts: Vec<PublishedPostResponseData>,
+}
+
+impl ListPublishedPostsHttpResponseBody {
+    pub fn new(res: &ListPostResponse, format: ContentFormat) -> Self {
+        Self {
+            total: res.total,
+            posts: res
+                .posts
+                .iter()
+                .map(|post| PublishedPostResponseData::new(post, format))
+                .collect(),
+        }
+    }
+}
+
+pub async fn list_published_posts<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Path(username): Path<String>,
+    Query(body): Query<ListPublishedPostsHttpRequestBody>,
+) -> Result<ApiSuccess<ListPublishedPostsHttpResponseBody>, ApiError> {
+    let format = body.format;
+    let domain_req = body.try_into_domain(username)?;
+    state
+        .blog_service
+        .list_post(domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref res| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListPublishedPostsHttpResponseBody::new(res, format),
+            )
+        })
+}
@@ -0,0 +1,48 @@
+use axum::{extract::State, http::StatusCode};
+use serde::Serialize;
+
+use crate::{
+    domain::blog::{models::policies::RoleGrouping, ports::BlogService},
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct RoleGroupingInfo {
+    pub subject: String,
+    pub role: String,
+}
+
+impl From<&RoleGrouping> for RoleGroupingInfo {
+    fn from(grouping: &RoleGrouping) -> Self {
+        Self {
+            subject: grouping.subject.clone(),
+            role: grouping.role.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ListRoleGroupingsHttpResponseBody {
+    pub groupings: Vec<RoleGroupingInfo>,
+}
+
+pub async fn list_role_groupings<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+) -> Result<ApiSuccess<ListRoleGroupingsHttpResponseBody>, ApiError> {
+    state
+        .blog_service
+        .list_role_groupings()
+        .await
+        .map_err(ApiError::from)
+        .map(|ref groupings| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListRoleGroupingsHttpResponseBody {
+                    groupings: groupings.iter().map(RoleGroupingInfo::from).collect(),
+                },
+            )
+        })
+}
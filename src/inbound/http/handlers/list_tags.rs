@@ -0,0 +1,48 @@
+use axum::{extract::State, http::StatusCode};
+use serde::Serialize;
+
+use crate::{
+    domain::blog::{models::tags::Tag, ports::BlogService},
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct TagInfo {
+    pub name: String,
+    pub post_count: i64,
+}
+
+impl From<&Tag> for TagInfo {
+    fn from(tag: &Tag) -> Self {
+        Self {
+            name: tag.name.clone(),
+            post_count: tag.post_count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ListTagsHttpResponseBody {
+    pub tags: Vec<TagInfo>,
+}
+
+pub async fn list_tags<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+) -> Result<ApiSuccess<ListTagsHttpResponseBody>, ApiError> {
+    state
+        .blog_service
+        .list_tags()
+        .await
+        .map_err(ApiError::from)
+        .map(|ref tags| {
+            ApiSuccess::new(
+                StatusCode::OK,
+                ListTagsHttpResponseBody {
+                    tags: tags.iter().map(TagInfo::from).collect(),
+                },
+            )
+        })
+}
@@ -0,0 +1,93 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{
+            posts::{ListPostResponse, ListTrashedPostRequest, Post},
+            users::User,
+        },
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListTrashedPostsHttpRequestBody {
+    pub offset: u32,
+    pub limit: u32,
+    pub author: Option<String>,
+}
+
+impl ListTrashedPostsHttpRequestBody {
+    fn try_into_domain(self, username: &str) -> Result<ListTrashedPostRequest, Error> {
+        let req = ListTrashedPostRequest::new(
+            self.offset,
+            self.limit,
+            username.to_string(),
+            self.author,
+        )?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct TrashedPostInfo {
+    pub post_id: String,
+    pub title: String,
+    pub slug: String,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Post> for TrashedPostInfo {
+    fn from(post: &Post) -> Self {
+        Self {
+            post_id: post.id.to_string(),
+            title: post.title.clone(),
+            slug: post.slug.clone(),
+            deleted_at: post.deleted_at,
+            created_at: post.created_at,
+            updated_at: post.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ListTrashedPostsHttpResponseBody {
+    pub total: u64,
+    pub posts: Vec<TrashedPostInfo>,
+}
+
+impl From<&ListPostResponse> for ListTrashedPostsHttpResponseBody {
+    fn from(res: &ListPostResponse) -> Self {
+        Self {
+            total: res.total,
+            posts: res.posts.iter().map(TrashedPostInfo::from).collect(),
+        }
+    }
+}
+
+pub async fn list_trashed_posts<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Query(body): Query<ListTrashedPostsHttpRequestBody>,
+) -> Result<ApiSuccess<ListTrashedPostsHttpResponseBody>, ApiError> {
+    let domain_req = body.try_into_domain(&user.username)?;
+    state
+        .blog_service
+        .list_trashed_posts(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref res| ApiSuccess::new(StatusCode::OK, res.into()))
+}
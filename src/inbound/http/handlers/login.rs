@@ -1,13 +1,28 @@
-use axum::{extract::State, http::StatusCode, Json};
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 use crate::{
-    domain::blog::{error::Error, models::users::LoginRequest, ports::BlogService},
+    config::{AuthSettings, LoginThrottleSettings},
+    domain::blog::{
+        error::Error,
+        models::{
+            auth::{LoginPolicy, LoginResponse, LoginThrottle, TokenPair},
+            users::LoginRequest,
+        },
+        ports::BlogService,
+    },
     inbound::http::{
         http_server::AppState,
         response::{ApiError, ApiSuccess},
     },
+    utils::jwt::JWT,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
@@ -19,10 +34,21 @@ pub struct LoginHttpRequest {
 impl LoginHttpRequest {
     pub fn try_into_domain(
         self,
-        jwt_secret: String,
+        jwt: JWT,
         expiration: u64,
+        refresh_expiration: u64,
+        ip: Option<String>,
+        policy: LoginPolicy,
     ) -> Result<LoginRequest, Error> {
-        let req = LoginRequest::new(self.username, self.password, jwt_secret, expiration)?;
+        let req = LoginRequest::new(
+            self.username,
+            self.password,
+            jwt,
+            expiration,
+            refresh_expiration,
+            ip,
+            policy,
+        )?;
         req.validate()?;
         Ok(req)
     }
@@ -31,19 +57,113 @@ impl LoginHttpRequest {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct LoginHttpResponseData {
     pub token: String,
+    pub refresh_token: String,
+    pub expires_in: u64,
+}
+
+impl From<&TokenPair> for LoginHttpResponseData {
+    fn from(tokens: &TokenPair) -> Self {
+        Self {
+            token: tokens.access_token.clone(),
+            refresh_token: tokens.refresh_token.clone(),
+            expires_in: tokens.expires_in,
+        }
+    }
+}
+
+/// Sent instead of the tokens to users with 2FA; `challenge_token` goes to
+/// `POST /api/auth/2fa/verify` together with a code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TwoFactorChallengeResponseData {
+    pub two_factor_required: bool,
+    pub challenge_token: String,
+    pub expires_in: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum LoginResponseData {
+    Authenticated(LoginHttpResponseData),
+    TwoFactorRequired(TwoFactorChallengeResponseData),
+}
+
+impl From<&LoginResponse> for LoginResponseData {
+    fn from(res: &LoginResponse) -> Self {
+        match res {
+            LoginResponse::Authenticated(tokens) => Self::Authenticated(tokens.into()),
+            LoginResponse::TwoFactorRequired {
+                challenge_token,
+                expires_in,
+            } => Self::TwoFactorRequired(TwoFactorChallengeResponseData {
+                two_factor_required: true,
+                challenge_token: challenge_token.clone(),
+                expires_in: *expires_in,
+            }),
+        }
+    }
+}
+
+pub fn login_throttle(settings: &LoginThrottleSettings) -> LoginThrottle {
+    LoginThrottle {
+        max_failures: settings.max_failures,
+        ip_max_failures: settings.ip_max_failures,
+        backoff_base: settings.backoff_base,
+        backoff_max: settings.backoff_max,
+        lockout_duration: settings.lockout_duration,
+    }
+}
+
+/// Who may log in, shared by every step of the login.
+pub fn login_policy(settings: &AuthSettings) -> LoginPolicy {
+    LoginPolicy {
+        require_verified_email: settings.email_verification.required_for_login(),
+        throttle: login_throttle(&settings.login_throttle),
+        challenge_expiration: settings.two_factor.challenge_expiration,
+    }
+}
+
+/// The peer address, or the first `X-Forwarded-For` hop when the proxy in
+/// front of us is trusted to set it.
+fn client_ip(
+    headers: &HeaderMap,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    trust_forwarded_for: bool,
+) -> Option<String> {
+    if trust_forwarded_for {
+        let forwarded = headers
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(|ip| ip.trim().to_string())
+            .filter(|ip| !ip.is_empty());
+        if forwarded.is_some() {
+            return forwarded;
+        }
+    }
+    connect_info.map(|ConnectInfo(addr)| addr.ip().to_string())
 }
 
 pub async fn login<BS: BlogService>(
     State(state): State<AppState<BS>>,
+    headers: HeaderMap,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
     Json(body): Json<LoginHttpRequest>,
-) -> Result<ApiSuccess<LoginHttpResponseData>, ApiError> {
+) -> Result<ApiSuccess<LoginResponseData>, ApiError> {
+    let throttle = &state.config.auth.login_throttle;
+    let ip = client_ip(&headers, connect_info, throttle.trust_forwarded_for);
     let jwt_expiration = state.config.auth.expiration;
-    let jwt_secret = state.config.auth.secret.clone();
-    let domain_req = body.try_into_domain(jwt_secret, jwt_expiration)?;
+    let refresh_expiration = state.config.auth.refresh_expiration;
+    let domain_req = body.try_into_domain(
+        state.jwt.clone(),
+        jwt_expiration,
+        refresh_expiration,
+        ip,
+        login_policy(&state.config.auth),
+    )?;
     state
         .blog_service
         .login(&domain_req)
         .await
         .map_err(ApiError::from)
-        .map(|token| ApiSuccess::new(StatusCode::OK, LoginHttpResponseData { token }))
+        .map(|ref res| ApiSuccess::new(StatusCode::OK, res.into()))
 }
@@ -0,0 +1,43 @@
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{error::Error, models::auth::LogoutRequest, ports::BlogService},
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+    utils::jwt::UserClaims,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct LogoutHttpRequest {
+    pub refresh_token: Option<String>,
+}
+
+impl LogoutHttpRequest {
+    pub fn try_into_domain(self, claims: &UserClaims) -> Result<LogoutRequest, Error> {
+        let req = LogoutRequest::new(
+            claims.jti.clone(),
+            claims.pid.clone(),
+            claims.exp,
+            self.refresh_token,
+        )?;
+        Ok(req)
+    }
+}
+
+pub async fn logout<BS: BlogService>(
+    Extension(claims): Extension<UserClaims>,
+    State(state): State<AppState<BS>>,
+    body: Option<Json<LogoutHttpRequest>>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    let domain_req = body.try_into_domain(&claims)?;
+    state
+        .blog_service
+        .logout(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
@@ -0,0 +1,25 @@
+use axum::{extract::State, http::StatusCode, Extension};
+
+use crate::{
+    domain::blog::{
+        models::{auth::LogoutAllRequest, users::User},
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+pub async fn logout_all<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let domain_req = LogoutAllRequest::new(user.id)?;
+    state
+        .blog_service
+        .logout_all(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
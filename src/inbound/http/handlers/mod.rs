@@ -1,9 +1,51 @@
+pub mod assign_role;
 pub mod batch_delete_post;
+pub mod change_password;
+pub mod confirm_totp;
+pub mod create_access_token;
+pub mod create_policy;
 pub mod create_post;
+pub mod create_role_grouping;
 pub mod create_user;
+pub mod delete_policy;
 pub mod delete_post;
+pub mod delete_role_grouping;
+pub mod delete_trashed_post;
 pub mod delete_user;
+pub mod diff_post_revisions;
+pub mod disable_totp;
+pub mod enroll_totp;
+pub mod explain_permission;
+pub mod forgot_password;
+pub mod get_post;
+pub mod get_post_by_slug;
+pub mod get_published_post;
+pub mod get_published_post_by_slug;
 pub mod get_user;
+pub mod get_user_roles;
+pub mod jwks;
+pub mod list_access_tokens;
+pub mod list_policies;
 pub mod list_post;
+pub mod list_post_revisions;
+pub mod list_published_posts;
+pub mod list_role_groupings;
+pub mod list_tags;
+pub mod list_trashed_posts;
 pub mod login;
+pub mod logout;
+pub mod logout_all;
+pub mod oidc_authorize;
+pub mod oidc_callback;
+pub mod refresh_token;
+pub mod reset_password;
+pub mod restore_post_revision;
+pub mod restore_trashed_post;
+pub mod revoke_access_token;
+pub mod revoke_role;
+pub mod search_post;
+pub mod unlock_user;
 pub mod update_post;
+pub mod update_post_status;
+pub mod verify_email;
+pub mod verify_two_factor;
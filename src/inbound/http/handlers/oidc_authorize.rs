@@ -0,0 +1,26 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    domain::blog::{error::Error, models::oidc::StartOidcLoginRequest, ports::BlogService},
+    inbound::http::{http_server::AppState, response::ApiError},
+};
+
+/// Sends the browser to the identity provider; it comes back through
+/// `GET /api/auth/oidc/callback`.
+pub async fn oidc_authorize<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+) -> Result<Response, ApiError> {
+    let settings = state
+        .config
+        .auth
+        .oidc
+        .as_ref()
+        .ok_or_else(|| Error::NotFound("single sign-on is not configured".to_string()))?;
+    let domain_req = StartOidcLoginRequest::new(settings.state_expiration)?;
+    let url = state.blog_service.start_oidc_login(&domain_req).await?;
+    Ok((StatusCode::FOUND, [(header::LOCATION, url)]).into_response())
+}
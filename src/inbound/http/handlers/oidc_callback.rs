@@ -0,0 +1,91 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{auth::LoginPolicy, oidc::OidcCallbackRequest, roles::Role},
+        ports::BlogService,
+    },
+    inbound::http::{
+        handlers::login::{login_policy, LoginResponseData},
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+    utils::jwt::JWT,
+};
+
+/// Query string the provider redirects back with: either `code` and `state`,
+/// or an `error` when the user or the provider aborted the login.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OidcCallbackHttpRequest {
+    #[serde(default)]
+    pub code: String,
+    #[serde(default)]
+    pub state: String,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+impl OidcCallbackHttpRequest {
+    pub fn try_into_domain(
+        self,
+        auto_provision: bool,
+        default_role: Role,
+        jwt: JWT,
+        expiration: u64,
+        refresh_expiration: u64,
+        policy: LoginPolicy,
+    ) -> Result<OidcCallbackRequest, Error> {
+        if let Some(error) = self.error {
+            let message = match self.error_description {
+                Some(description) => format!("{error}: {description}"),
+                None => error,
+            };
+            return Err(Error::Unauthorized(format!(
+                "identity provider refused the login: {message}"
+            )));
+        }
+        OidcCallbackRequest::new(
+            self.code,
+            self.state,
+            auto_provision,
+            default_role,
+            jwt,
+            expiration,
+            refresh_expiration,
+            policy,
+        )
+    }
+}
+
+pub async fn oidc_callback<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Query(query): Query<OidcCallbackHttpRequest>,
+) -> Result<ApiSuccess<LoginResponseData>, ApiError> {
+    let settings = state
+        .config
+        .auth
+        .oidc
+        .as_ref()
+        .ok_or_else(|| Error::NotFound("single sign-on is not configured".to_string()))?;
+    let jwt_expiration = state.config.auth.expiration;
+    let refresh_expiration = state.config.auth.refresh_expiration;
+    let domain_req = query.try_into_domain(
+        settings.auto_provision,
+        state.config.auth.default_role,
+        state.jwt.clone(),
+        jwt_expiration,
+        refresh_expiration,
+        login_policy(&state.config.auth),
+    )?;
+    state
+        .blog_service
+        .finish_oidc_login(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref res| ApiSuccess::new(StatusCode::OK, res.into()))
+}
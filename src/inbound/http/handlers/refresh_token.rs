@@ -0,0 +1,56 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{error::Error, models::auth::RefreshTokenRequest, ports::BlogService},
+    inbound::http::{
+        handlers::login::LoginHttpResponseData,
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+    utils::jwt::JWT,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RefreshTokenHttpRequest {
+    pub refresh_token: String,
+}
+
+impl RefreshTokenHttpRequest {
+    pub fn try_into_domain(
+        self,
+        jwt: JWT,
+        expiration: u64,
+        refresh_expiration: u64,
+        require_verified_email: bool,
+    ) -> Result<RefreshTokenRequest, Error> {
+        let req = RefreshTokenRequest::new(
+            self.refresh_token,
+            jwt,
+            expiration,
+            refresh_expiration,
+            require_verified_email,
+        )?;
+        Ok(req)
+    }
+}
+
+pub async fn refresh_token<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Json(body): Json<RefreshTokenHttpRequest>,
+) -> Result<ApiSuccess<LoginHttpResponseData>, ApiError> {
+    let jwt_expiration = state.config.auth.expiration;
+    let refresh_expiration = state.config.auth.refresh_expiration;
+    let domain_req = body.try_into_domain(
+        state.jwt.clone(),
+        jwt_expiration,
+        refresh_expiration,
+        state.config.auth.email_verification.required_for_login(),
+    )?;
+    state
+        .blog_service
+        .refresh_token(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref tokens| ApiSuccess::new(StatusCode::OK, tokens.into()))
+}
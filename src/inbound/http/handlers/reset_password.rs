@@ -0,0 +1,36 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{error::Error, models::auth::ResetPasswordRequest, ports::BlogService},
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ResetPasswordHttpRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+impl ResetPasswordHttpRequest {
+    pub fn try_into_domain(self) -> Result<ResetPasswordRequest, Error> {
+        let req = ResetPasswordRequest::new(self.token, self.new_password)?;
+        Ok(req)
+    }
+}
+
+pub async fn reset_password<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Json(body): Json<ResetPasswordHttpRequest>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let domain_req = body.try_into_domain()?;
+    state
+        .blog_service
+        .reset_password(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
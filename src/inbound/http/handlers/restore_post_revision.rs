@@ -0,0 +1,46 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{revisions::RestorePostRevisionRequest, users::User},
+        ports::BlogService,
+    },
+    inbound::http::{
+        handlers::update_post::UpdatePostResponseData,
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RestorePostRevisionHttpRequest {
+    pub id: String,
+    pub revision: i32,
+}
+
+impl RestorePostRevisionHttpRequest {
+    pub fn try_into_domain(self, username: &str) -> Result<RestorePostRevisionRequest, Error> {
+        let req = RestorePostRevisionRequest::new(self.id, username.to_string(), self.revision)?;
+        Ok(req)
+    }
+}
+
+pub async fn restore_post_revision<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Path(body): Path<RestorePostRevisionHttpRequest>,
+) -> Result<ApiSuccess<UpdatePostResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(&user.username)?;
+    state
+        .blog_service
+        .restore_post_revision(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref post| ApiSuccess::new(StatusCode::OK, post.into()))
+}
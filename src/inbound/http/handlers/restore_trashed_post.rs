@@ -0,0 +1,31 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+
+use crate::{
+    domain::blog::{
+        models::{posts::TrashedPostRequest, users::User},
+        ports::BlogService,
+    },
+    inbound::http::{
+        handlers::update_post::UpdatePostResponseData,
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+pub async fn restore_trashed_post<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<UpdatePostResponseData>, ApiError> {
+    let domain_req = TrashedPostRequest::new(id, user.username)?;
+    state
+        .blog_service
+        .restore_trashed_post(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref post| ApiSuccess::new(StatusCode::OK, post.into()))
+}
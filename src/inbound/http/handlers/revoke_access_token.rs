@@ -0,0 +1,30 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+
+use crate::{
+    domain::blog::{
+        models::{access_tokens::RevokeAccessTokenRequest, users::User},
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+pub async fn revoke_access_token<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Path(id): Path<String>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let domain_req = RevokeAccessTokenRequest::new(user.id, id)?;
+    state
+        .blog_service
+        .revoke_access_token(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
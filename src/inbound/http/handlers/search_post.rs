@@ -0,0 +1,99 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Extension,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{
+            posts::PostStatus,
+            search::{SearchPostRequest, SearchPostResponse, SearchPostResult},
+            users::User,
+        },
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchPostHttpRequestBody {
+    pub q: String,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+impl SearchPostHttpRequestBody {
+    fn try_into_domain(self, username: &str) -> Result<SearchPostRequest, Error> {
+        let req = SearchPostRequest::new(self.q, self.offset, self.limit, username.to_string())?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SearchPostHit {
+    pub post_id: String,
+    pub title: String,
+    pub slug: String,
+    pub status: PostStatus,
+    pub tags: Vec<String>,
+    pub rank: f32,
+    pub title_highlight: String,
+    pub snippet: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&SearchPostResult> for SearchPostHit {
+    fn from(res: &SearchPostResult) -> Self {
+        Self {
+            post_id: res.post.id.to_string(),
+            title: res.post.title.clone(),
+            slug: res.post.slug.clone(),
+            status: res.post.status,
+            tags: res.post.tags.clone(),
+            rank: res.rank,
+            title_highlight: res.title_highlight.clone(),
+            snippet: res.snippet.clone(),
+            published_at: res.post.published_at,
+            created_at: res.post.created_at,
+            updated_at: res.post.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SearchPostHttpResponseBody {
+    pub total: u64,
+    pub results: Vec<SearchPostHit>,
+}
+
+impl From<&SearchPostResponse> for SearchPostHttpResponseBody {
+    fn from(res: &SearchPostResponse) -> Self {
+        Self {
+            total: res.total,
+            results: res.results.iter().map(SearchPostHit::from).collect(),
+        }
+    }
+}
+
+pub async fn search_post<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Query(body): Query<SearchPostHttpRequestBody>,
+) -> Result<ApiSuccess<SearchPostHttpResponseBody>, ApiError> {
+    let domain_req = body.try_into_domain(&user.username)?;
+    state
+        .blog_service
+        .search_post(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref res| ApiSuccess::new(StatusCode::OK, res.into()))
+}
@@ -0,0 +1,25 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+use crate::{
+    domain::blog::{models::auth::UnlockUserRequest, ports::BlogService},
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+pub async fn unlock_user<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Path(username): Path<String>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let domain_req = UnlockUserRequest::new(username)?;
+    state
+        .blog_service
+        .unlock_user(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
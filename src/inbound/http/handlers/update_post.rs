@@ -10,7 +10,7 @@ use crate::{
     domain::blog::{
         error::Error,
         models::{
-            posts::{Post, UpdatePostRequest},
+            posts::{Post, PostStatus, UpdatePostRequest},
             users::User,
         },
         ports::BlogService,
@@ -25,11 +25,20 @@ use crate::{
 pub struct UpdatePostHttpRequestBody {
     pub title: String,
     pub content: String,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub tags: Option<Vec<String>>,
 }
 
 impl UpdatePostHttpRequestBody {
     fn try_into_domain(self, id: String, username: &str) -> Result<UpdatePostRequest, Error> {
-        let req = UpdatePostRequest::new(id, self.title, self.content, username.to_string())?;
+        let req = UpdatePostRequest::new(
+            id,
+            self.title,
+            self.content,
+            username.to_string(),
+            self.publish_at,
+            self.tags,
+        )?;
         Ok(req)
     }
 }
@@ -38,9 +47,14 @@ impl UpdatePostHttpRequestBody {
 pub struct UpdatePostResponseData {
     pub id: String,
     pub title: String,
+    pub slug: String,
     pub content: String,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub publish_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub tags: Vec<String>,
 }
 
 impl From<&Post> for UpdatePostResponseData {
@@ -48,9 +62,14 @@ impl From<&Post> for UpdatePostResponseData {
         Self {
             id: post.id.to_string(),
             title: post.title.clone(),
+            slug: post.slug.clone(),
             content: post.content.clone(),
+            status: post.status,
+            published_at: post.published_at,
+            publish_at: post.publish_at,
             created_at: post.created_at,
             updated_at: post.updated_at,
+            tags: post.tags.clone(),
         }
     }
 }
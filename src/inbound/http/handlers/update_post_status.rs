@@ -0,0 +1,68 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{
+            posts::{ChangePostStatusRequest, Post, PostStatus},
+            users::User,
+        },
+        ports::BlogService,
+    },
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct UpdatePostStatusHttpRequestBody {
+    pub status: PostStatus,
+}
+
+impl UpdatePostStatusHttpRequestBody {
+    fn try_into_domain(self, id: String, username: &str) -> Result<ChangePostStatusRequest, Error> {
+        let req = ChangePostStatusRequest::new(id, username.to_string(), self.status)?;
+        Ok(req)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpdatePostStatusResponseData {
+    pub id: String,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Post> for UpdatePostStatusResponseData {
+    fn from(post: &Post) -> Self {
+        Self {
+            id: post.id.to_string(),
+            status: post.status,
+            published_at: post.published_at,
+            updated_at: post.updated_at,
+        }
+    }
+}
+
+pub async fn update_post_status<BS: BlogService>(
+    Extension(user): Extension<User>,
+    State(state): State<AppState<BS>>,
+    Path(id): Path<String>,
+    Json(body): Json<UpdatePostStatusHttpRequestBody>,
+) -> Result<ApiSuccess<UpdatePostStatusResponseData>, ApiError> {
+    let domain_req = body.try_into_domain(id, &user.username)?;
+    state
+        .blog_service
+        .change_post_status(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref post| ApiSuccess::new(StatusCode::OK, post.into()))
+}
@@ -0,0 +1,35 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{error::Error, models::auth::VerifyEmailRequest, ports::BlogService},
+    inbound::http::{
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct VerifyEmailHttpRequest {
+    pub token: String,
+}
+
+impl VerifyEmailHttpRequest {
+    pub fn try_into_domain(self) -> Result<VerifyEmailRequest, Error> {
+        let req = VerifyEmailRequest::new(self.token)?;
+        Ok(req)
+    }
+}
+
+pub async fn verify_email<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Json(body): Json<VerifyEmailHttpRequest>,
+) -> Result<ApiSuccess<()>, ApiError> {
+    let domain_req = body.try_into_domain()?;
+    state
+        .blog_service
+        .verify_email(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|_| ApiSuccess::new(StatusCode::NO_CONTENT, ()))
+}
@@ -0,0 +1,62 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Deserialize;
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{auth::LoginPolicy, two_factor::VerifyTwoFactorRequest},
+        ports::BlogService,
+    },
+    inbound::http::{
+        handlers::login::{login_policy, LoginHttpResponseData},
+        http_server::AppState,
+        response::{ApiError, ApiSuccess},
+    },
+    utils::jwt::JWT,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct VerifyTwoFactorHttpRequest {
+    pub challenge_token: String,
+    pub code: String,
+}
+
+impl VerifyTwoFactorHttpRequest {
+    pub fn try_into_domain(
+        self,
+        jwt: JWT,
+        expiration: u64,
+        refresh_expiration: u64,
+        policy: LoginPolicy,
+    ) -> Result<VerifyTwoFactorRequest, Error> {
+        let req = VerifyTwoFactorRequest::new(
+            self.challenge_token,
+            self.code,
+            jwt,
+            expiration,
+            refresh_expiration,
+            policy,
+        )?;
+        Ok(req)
+    }
+}
+
+pub async fn verify_two_factor<BS: BlogService>(
+    State(state): State<AppState<BS>>,
+    Json(body): Json<VerifyTwoFactorHttpRequest>,
+) -> Result<ApiSuccess<LoginHttpResponseData>, ApiError> {
+    let jwt_expiration = state.config.auth.expiration;
+    let refresh_expiration = state.config.auth.refresh_expiration;
+    let domain_req = body.try_into_domain(
+        state.jwt.clone(),
+        jwt_expiration,
+        refresh_expiration,
+        login_policy(&state.config.auth),
+    )?;
+    state
+        .blog_service
+        .verify_two_factor(&domain_req)
+        .await
+        .map_err(ApiError::from)
+        .map(|ref tokens| ApiSuccess::new(StatusCode::OK, tokens.into()))
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use anyhow::{Context, Ok};
 
@@ -9,20 +9,29 @@ use axum::{
 };
 use tokio::net;
 
-use crate::{config::Settings, domain::blog::ports::BlogService};
+use crate::{config::Settings, domain::blog::ports::BlogService, utils::jwt::JWT};
 
 use super::{
     handlers::{
-        batch_delete_post, create_post, create_user, delete_post, delete_user, get_user, list_post,
-        login, update_post,
+        assign_role, batch_delete_post, change_password, confirm_totp, create_access_token,
+        create_policy, create_post, create_role_grouping, create_user, delete_policy, delete_post,
+        delete_role_grouping, delete_trashed_post, delete_user, diff_post_revisions, disable_totp,
+        enroll_totp, explain_permission, forgot_password, get_post, get_post_by_slug,
+        get_published_post, get_published_post_by_slug, get_user, get_user_roles, jwks,
+        list_access_tokens, list_policies, list_post, list_post_revisions, list_published_posts,
+        list_role_groupings, list_tags, list_trashed_posts, login, logout, logout_all,
+        oidc_authorize, oidc_callback, refresh_token, reset_password, restore_post_revision,
+        restore_trashed_post, revoke_access_token, revoke_role, search_post, unlock_user,
+        update_post, update_post_status, verify_email, verify_two_factor,
     },
-    middlewares::{auth, permission},
+    middlewares::{auth, permission, problem_json, request_id},
 };
 
 #[derive(Debug, Clone)]
 pub struct AppState<BS: BlogService> {
     pub blog_service: Arc<BS>,
     pub config: Settings,
+    pub jwt: JWT,
 }
 
 pub struct HttpServer {
@@ -31,22 +40,9 @@ pub struct HttpServer {
 }
 
 impl HttpServer {
-    pub async fn new(blog_service: impl BlogService, config: Settings) -> anyhow::Result<Self> {
-        let trace_layer = tower_http::trace::TraceLayer::new_for_http().make_span_with(
-            |request: &axum::extract::Request<_>| {
-                let uri = request.uri().to_string();
-                tracing::info_span!("http_request", method = ?request.method(), uri)
-            },
-        );
-        let state = AppState {
-            blog_service: Arc::new(blog_service),
-            config: config.clone(),
-        };
-        let router = Router::new()
-            .nest("/api", api_routes(state.clone()))
-            .layer(trace_layer)
-            .with_state(state);
+    pub async fn new<BS: BlogService>(blog_service: BS, config: Settings) -> anyhow::Result<Self> {
         let application_settings = config.application.clone();
+        let router = router(blog_service, config)?;
 
         let listener = net::TcpListener::bind(format!(
             "{}:{}",
@@ -60,13 +56,42 @@ impl HttpServer {
 
     pub async fn run(self) -> anyhow::Result<()> {
         tracing::debug!("listening on {}", self.listener.local_addr().unwrap());
-        axum::serve(self.listener, self.router)
-            .await
-            .context("received error from running server")?;
+        axum::serve(
+            self.listener,
+            self.router
+                .into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .context("received error from running server")?;
         Ok(())
     }
 }
 
+/// The application with every route and middleware, ready to be served.
+pub fn router<BS: BlogService>(blog_service: BS, config: Settings) -> anyhow::Result<Router> {
+    let trace_layer = tower_http::trace::TraceLayer::new_for_http().make_span_with(
+        |request: &axum::extract::Request<_>| {
+            let uri = request.uri().to_string();
+            let request_id = request_id::current_request_id().unwrap_or_default();
+            tracing::info_span!("http_request", method = ?request.method(), uri, request_id)
+        },
+    );
+    let jwt = JWT::from_settings(&config.auth).context("failed to load jwt keys")?;
+    let state = AppState {
+        blog_service: Arc::new(blog_service),
+        config: config.clone(),
+        jwt,
+    };
+    let router = Router::new()
+        .route("/.well-known/jwks.json", get(jwks::jwks::<BS>))
+        .nest("/api", api_routes(state.clone()))
+        .layer(trace_layer)
+        .layer(middleware::from_fn(problem_json::problem_json_middleware))
+        .layer(middleware::from_fn(request_id::request_id_middleware))
+        .with_state(state);
+    Ok(router)
+}
+
 fn api_routes<BS>(state: AppState<BS>) -> Router<AppState<BS>>
 where
     BS: BlogService + 'static,
@@ -75,8 +100,107 @@ where
         .nest(
             "/auth",
             Router::new()
+                .route("/logout", post(logout::logout::<BS>))
+                .route("/logout-all", post(logout_all::logout_all::<BS>))
+                .route("/password", put(change_password::change_password::<BS>))
+                .route("/2fa/enroll", post(enroll_totp::enroll_totp::<BS>))
+                .route("/2fa/confirm", post(confirm_totp::confirm_totp::<BS>))
+                .route("/2fa", delete(disable_totp::disable_totp::<BS>))
+                .route(
+                    "/tokens",
+                    post(create_access_token::create_access_token::<BS>),
+                )
+                .route("/tokens", get(list_access_tokens::list_access_tokens::<BS>))
+                .route(
+                    "/tokens/:id",
+                    delete(revoke_access_token::revoke_access_token::<BS>),
+                )
+                .route_layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    auth::auth_middleware::<BS>,
+                ))
                 .route("/login", post(login::login::<BS>))
-                .route("/register", post(create_user::create_user::<BS>)),
+                .route("/refresh", post(refresh_token::refresh_token::<BS>))
+                .route(
+                    "/2fa/verify",
+                    post(verify_two_factor::verify_two_factor::<BS>),
+                )
+                .route(
+                    "/forgot-password",
+                    post(forgot_password::forgot_password::<BS>),
+                )
+                .route(
+                    "/reset-password",
+                    post(reset_password::reset_password::<BS>),
+                )
+                .route("/register", post(create_user::create_user::<BS>))
+                .route("/verify-email", post(verify_email::verify_email::<BS>))
+                .route("/oidc/authorize", get(oidc_authorize::oidc_authorize::<BS>))
+                .route("/oidc/callback", get(oidc_callback::oidc_callback::<BS>)),
+        )
+        .nest(
+            "/admin",
+            Router::new()
+                .route(
+                    "/users/:username/lockout",
+                    delete(unlock_user::unlock_user::<BS>),
+                )
+                .route(
+                    "/users/:username/roles",
+                    get(get_user_roles::get_user_roles::<BS>),
+                )
+                .route(
+                    "/users/:username/roles/:role",
+                    put(assign_role::assign_role::<BS>),
+                )
+                .route(
+                    "/users/:username/roles/:role",
+                    delete(revoke_role::revoke_role::<BS>),
+                )
+                .route("/policies", get(list_policies::list_policies::<BS>))
+                .route("/policies", post(create_policy::create_policy::<BS>))
+                .route("/policies", delete(delete_policy::delete_policy::<BS>))
+                .route(
+                    "/groupings",
+                    get(list_role_groupings::list_role_groupings::<BS>),
+                )
+                .route(
+                    "/groupings",
+                    post(create_role_grouping::create_role_grouping::<BS>),
+                )
+                .route(
+                    "/groupings",
+                    delete(delete_role_grouping::delete_role_grouping::<BS>),
+                )
+                .route(
+                    "/permissions/explain",
+                    get(explain_permission::explain_permission::<BS>),
+                )
+                // The last layer runs first: authenticate, then check the policy.
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    permission::permission_middleware::<BS>,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    auth::auth_middleware::<BS>,
+                )),
+        )
+        .nest(
+            "/public",
+            Router::new()
+                .route(
+                    "/users/:username/posts",
+                    get(list_published_posts::list_published_posts::<BS>),
+                )
+                .route(
+                    "/posts/:id",
+                    get(get_published_post::get_published_post::<BS>),
+                )
+                .route(
+                    "/posts/by-slug/:username/:slug",
+                    get(get_published_post_by_slug::get_published_post_by_slug::<BS>),
+                ),
         )
         .nest(
             "/users",
@@ -85,11 +209,11 @@ where
                 .route("/:username", delete(delete_user::delete_user::<BS>))
                 .layer(middleware::from_fn_with_state(
                     state.clone(),
-                    auth::auth_middleware::<BS>,
+                    permission::permission_middleware::<BS>,
                 ))
                 .layer(middleware::from_fn_with_state(
                     state.clone(),
-                    permission::permission_middleware::<BS>,
+                    auth::auth_middleware::<BS>,
                 )),
         )
         .nest(
@@ -97,9 +221,53 @@ where
             Router::new()
                 .route("/", post(create_post::create_post::<BS>))
                 .route("/", get(list_post::list_post::<BS>))
+                .route("/:id", get(get_post::get_post::<BS>))
                 .route("/:id", put(update_post::update_post::<BS>))
                 .route("/:id", delete(delete_post::delete_post::<BS>))
+                .route(
+                    "/:id/status",
+                    put(update_post_status::update_post_status::<BS>),
+                )
+                .route(
+                    "/:id/revisions",
+                    get(list_post_revisions::list_post_revisions::<BS>),
+                )
+                .route(
+                    "/:id/revisions/diff",
+                    get(diff_post_revisions::diff_post_revisions::<BS>),
+                )
+                .route(
+                    "/:id/revisions/:revision/restore",
+                    post(restore_post_revision::restore_post_revision::<BS>),
+                )
                 .route("/", delete(batch_delete_post::batch_delete_post::<BS>))
+                .route("/search", get(search_post::search_post::<BS>))
+                .route("/trash", get(list_trashed_posts::list_trashed_posts::<BS>))
+                .route(
+                    "/trash/:id",
+                    delete(delete_trashed_post::delete_trashed_post::<BS>),
+                )
+                .route(
+                    "/trash/:id/restore",
+                    post(restore_trashed_post::restore_trashed_post::<BS>),
+                )
+                .route(
+                    "/by-slug/:username/:slug",
+                    get(get_post_by_slug::get_post_by_slug::<BS>),
+                )
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    permission::permission_middleware::<BS>,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    auth::auth_middleware::<BS>,
+                )),
+        )
+        .nest(
+            "/tags",
+            Router::new()
+                .route("/", get(list_tags::list_tags::<BS>))
                 .layer(middleware::from_fn_with_state(
                     state.clone(),
                     auth::auth_middleware::<BS>,
@@ -1,15 +1,18 @@
 use crate::{
     domain::blog::{
         error::Error,
-        models::users::{GetUserByIdRequest, User},
+        models::{
+            access_tokens::{TokenScope, ACCESS_TOKEN_PREFIX},
+            users::{GetUserByIdRequest, User},
+        },
         ports::BlogService,
     },
     inbound::http::{http_server::AppState, response::ApiError},
     utils::jwt,
 };
 use axum::{
-    extract::{Request, State},
-    http::HeaderMap,
+    extract::{OriginalUri, Request, State},
+    http::{HeaderMap, Method},
     middleware::Next,
     response::Response,
 };
@@ -19,23 +22,86 @@ const AUTH_HEADER: &str = "authorization";
 
 pub async fn auth_middleware<BS: BlogService>(
     State(state): State<AppState<BS>>,
+    OriginalUri(original_uri): OriginalUri,
     mut request: Request,
     next: Next,
 ) -> Result<Response, ApiError> {
     let headers = request.headers();
     let token = extract_token(headers).map_err(ApiError::from)?;
-    let user_claims = jwt_validate(&token, &state.config.auth.secret).map_err(ApiError::from)?;
+    if token.starts_with(ACCESS_TOKEN_PREFIX) {
+        let access_token = state
+            .blog_service
+            .authenticate_access_token(&token)
+            .await
+            .map_err(ApiError::from)?;
+        let scope = required_scope(original_uri.path(), request.method()).ok_or_else(|| {
+            ApiError::PermissionDenied("access tokens cannot be used here".to_string())
+        })?;
+        if !access_token.has_scope(scope) {
+            return Err(ApiError::PermissionDenied(format!(
+                "access token lacks the {} scope",
+                scope.as_str()
+            )));
+        }
+        let user = get_user(state.clone(), &access_token.user_id)
+            .await
+            .map_err(ApiError::from)?;
+        request.extensions_mut().insert(user);
+        request.extensions_mut().insert(access_token);
+        return Ok(next.run(request).await);
+    }
+    let user_claims = jwt_validate(&token, &state.jwt).map_err(ApiError::from)?;
+    let revoked = state
+        .blog_service
+        .is_token_revoked(&user_claims.jti)
+        .await
+        .map_err(ApiError::from)?;
+    if revoked {
+        return Err(ApiError::AuthorizationError(
+            "token has been revoked".to_string(),
+        ));
+    }
     let user = get_user(state.clone(), &user_claims.pid)
         .await
         .map_err(ApiError::from)?;
+    if !user.accepts_token_issued_at(user_claims.iat) {
+        return Err(ApiError::AuthorizationError(
+            "token has been revoked".to_string(),
+        ));
+    }
 
     request.extensions_mut().insert(user);
+    request.extensions_mut().insert(user_claims);
     Ok(next.run(request).await)
 }
 
-fn jwt_validate(token: &str, secret: &str) -> Result<jwt::UserClaims, Error> {
-    let jwt = jwt::JWT::new(secret);
-    let claims = jwt.validate(token)?;
+/// The scope an access token needs for a request. Access tokens only reach
+/// the post and tag endpoints, never account management.
+fn required_scope(path: &str, method: &Method) -> Option<TokenScope> {
+    let under = |prefix: &str| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    };
+    let read = matches!(*method, Method::GET | Method::HEAD);
+    if under("/api/posts") {
+        Some(if read {
+            TokenScope::PostsRead
+        } else {
+            TokenScope::PostsWrite
+        })
+    } else if under("/api/tags") && read {
+        Some(TokenScope::PostsRead)
+    } else {
+        None
+    }
+}
+
+/// Every way a token can fail validation (expired, bad signature, unknown
+/// key, malformed) means the caller is not authenticated.
+fn jwt_validate(token: &str, jwt: &jwt::JWT) -> Result<jwt::UserClaims, Error> {
+    let claims = jwt
+        .validate(token)
+        .map_err(|err| Error::Unauthorized(format!("invalid token: {err}")))?;
     Ok(claims.claims)
 }
 
@@ -1,2 +1,4 @@
 pub mod auth;
 pub mod permission;
+pub mod problem_json;
+pub mod request_id;
@@ -1,5 +1,11 @@
 use crate::{
-    domain::blog::{models::users::User, ports::BlogService},
+    domain::blog::{
+        models::{
+            roles::{casbin_path, user_subject},
+            users::User,
+        },
+        ports::BlogService,
+    },
     inbound::http::{http_server::AppState, response::ApiError},
 };
 use axum::{
@@ -19,8 +25,8 @@ pub async fn permission_middleware<BS: BlogService>(
         .extensions()
         .get::<User>()
         .ok_or_else(|| ApiError::PermissionDenied("permission denied".to_string()))?;
-    let sub = user.username.clone();
-    let obj = original_uri.path().to_string();
+    let sub = user_subject(&user.username);
+    let obj = casbin_path(original_uri.path());
     let act = request.method().to_string();
     info!("permission check: {sub} {obj} {act}");
     let permission = state
@@ -0,0 +1,86 @@
+use axum::{
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::inbound::http::response::{ApiErrorData, ErrorCode, FieldErrors};
+
+const PROBLEM_JSON: &str = "application/problem+json";
+const PROBLEM_TYPE_PREFIX: &str = "urn:blog-rs:problem:";
+
+/// An RFC 7807 problem details document. `code`, `request_id` and `fields`
+/// are extension members carrying the same data as the default envelope.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    pub instance: String,
+    pub code: ErrorCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<FieldErrors>,
+}
+
+/// Re-renders error responses as `application/problem+json` for clients that
+/// ask for it in `Accept`; everyone else keeps the regular response body.
+pub async fn problem_json_middleware(request: Request, next: Next) -> Response {
+    let wants_problem = prefers_problem_json(request.headers());
+    let instance = request.uri().path().to_string();
+    let response = next.run(request).await;
+    if !wants_problem {
+        return response;
+    }
+    let Some(data) = response.extensions().get::<ApiErrorData>().cloned() else {
+        return response;
+    };
+    let status = response.status();
+    let problem = ProblemDetails {
+        problem_type: format!("{PROBLEM_TYPE_PREFIX}{}", data.code.as_str()),
+        title: status.canonical_reason().unwrap_or_default().to_string(),
+        status: status.as_u16(),
+        detail: data.message,
+        instance,
+        code: data.code,
+        request_id: data.request_id,
+        fields: data.fields,
+    };
+    let mut problem_response = (status, Json(problem)).into_response();
+    problem_response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+    problem_response
+}
+
+/// Whether `application/problem+json` is acceptable and weighted at least as
+/// high as plain `application/json`.
+fn prefers_problem_json(headers: &HeaderMap) -> bool {
+    let mut problem_q = 0.0;
+    let mut json_q = 0.0;
+    for value in headers.get_all(header::ACCEPT) {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        for media_range in value.split(',') {
+            let mut parts = media_range.split(';').map(str::trim);
+            let media_type = parts.next().unwrap_or_default().to_ascii_lowercase();
+            let q = parts
+                .filter_map(|param| param.strip_prefix("q="))
+                .find_map(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            match media_type.as_str() {
+                PROBLEM_JSON => problem_q = f32::max(problem_q, q),
+                "application/json" => json_q = f32::max(json_q, q),
+                _ => {}
+            }
+        }
+    }
+    problem_q > 0.0 && problem_q >= json_q
+}
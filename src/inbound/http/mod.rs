@@ -1,3 +1,4 @@
+pub mod content_format;
 pub mod handlers;
 pub mod http_server;
 pub mod middlewares;
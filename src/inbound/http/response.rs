@@ -1,9 +1,14 @@
+use std::collections::BTreeMap;
+
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
 };
 use serde::Serialize;
+use validator::ValidationErrors;
+
+use super::middlewares::request_id;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ResponseBody<T: Serialize> {
@@ -55,16 +60,91 @@ impl<T: Serialize + PartialEq> ApiResponseBody<T> {
     }
 }
 
+/// Stable, machine-readable identifier of what went wrong, for clients to
+/// branch on instead of parsing `message`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InternalError,
+    UnprocessableEntity,
+    ValidationFailed,
+    PermissionDenied,
+    BadRequest,
+    Unauthorized,
+    NotFound,
+    Conflict,
+    TooManyRequests,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::InternalError => "internal_error",
+            ErrorCode::UnprocessableEntity => "unprocessable_entity",
+            ErrorCode::ValidationFailed => "validation_failed",
+            ErrorCode::PermissionDenied => "permission_denied",
+            ErrorCode::BadRequest => "bad_request",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::Conflict => "conflict",
+            ErrorCode::TooManyRequests => "too_many_requests",
+        }
+    }
+}
+
+/// One validation rule a field violated, e.g. `length` with `max: 50`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldViolation {
+    pub rule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, serde_json::Value>,
+}
+
+/// Violated rules keyed by field name.
+pub type FieldErrors = BTreeMap<String, Vec<FieldViolation>>;
+
+/// Flattens `errors` into field violations. The offending value itself is
+/// left out so secrets such as passwords are never echoed back.
+pub fn field_errors(errors: &ValidationErrors) -> FieldErrors {
+    errors
+        .field_errors()
+        .into_iter()
+        .map(|(field, errors)| {
+            let violations = errors
+                .iter()
+                .map(|err| FieldViolation {
+                    rule: err.code.to_string(),
+                    message: err.message.as_ref().map(|m| m.to_string()),
+                    params: err
+                        .params
+                        .iter()
+                        .filter(|(name, _)| *name != "value")
+                        .map(|(name, value)| (name.to_string(), value.clone()))
+                        .collect(),
+                })
+                .collect();
+            (field.to_string(), violations)
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ApiErrorData {
+    pub code: ErrorCode,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<FieldErrors>,
 }
 
 impl ApiResponseBody<ApiErrorData> {
-    pub fn new_error(status_code: StatusCode, message: String) -> Self {
+    pub fn new_error(status_code: StatusCode, data: ApiErrorData) -> Self {
         Self {
             status_code: status_code.as_u16(),
-            data: ApiErrorData { message },
+            data,
         }
     }
 }
@@ -73,51 +153,83 @@ impl ApiResponseBody<ApiErrorData> {
 pub enum ApiError {
     InternalServerError(String),
     UnprocessableEntity(String),
+    ValidationFailed(FieldErrors),
     PermissionDenied(String),
     BadRequestError(String),
     AuthorizationError(String),
+    NotFound(String),
+    Conflict(String),
+    TooManyRequests(String),
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
+impl ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::UnprocessableEntity(_) | ApiError::ValidationFailed(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ApiError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+            ApiError::BadRequestError(_) => StatusCode::BAD_REQUEST,
+            ApiError::AuthorizationError(_) => StatusCode::UNAUTHORIZED,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    fn code(&self) -> ErrorCode {
         match self {
+            ApiError::InternalServerError(_) => ErrorCode::InternalError,
+            ApiError::UnprocessableEntity(_) => ErrorCode::UnprocessableEntity,
+            ApiError::ValidationFailed(_) => ErrorCode::ValidationFailed,
+            ApiError::PermissionDenied(_) => ErrorCode::PermissionDenied,
+            ApiError::BadRequestError(_) => ErrorCode::BadRequest,
+            ApiError::AuthorizationError(_) => ErrorCode::Unauthorized,
+            ApiError::NotFound(_) => ErrorCode::NotFound,
+            ApiError::Conflict(_) => ErrorCode::Conflict,
+            ApiError::TooManyRequests(_) => ErrorCode::TooManyRequests,
+        }
+    }
+
+    fn into_data(self) -> ApiErrorData {
+        let code = self.code();
+        let (message, fields) = match self {
             ApiError::InternalServerError(e) => {
                 tracing::error!("{}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponseBody::new_error(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        "Internal server error".to_string(),
-                    )),
-                )
-                    .into_response()
+                ("Internal server error".to_string(), None)
             }
-            ApiError::UnprocessableEntity(message) => (
-                StatusCode::UNPROCESSABLE_ENTITY,
-                Json(ApiResponseBody::new_error(
-                    StatusCode::UNPROCESSABLE_ENTITY,
-                    message,
-                )),
-            )
-                .into_response(),
-            ApiError::BadRequestError(message) => (
-                StatusCode::BAD_REQUEST,
-                Json(ApiResponseBody::new_error(StatusCode::BAD_REQUEST, message)),
-            )
-                .into_response(),
-            ApiError::AuthorizationError(message) => (
-                StatusCode::UNAUTHORIZED,
-                Json(ApiResponseBody::new_error(
-                    StatusCode::UNAUTHORIZED,
-                    message,
-                )),
-            )
-                .into_response(),
-            ApiError::PermissionDenied(message) => (
-                StatusCode::FORBIDDEN,
-                Json(ApiResponseBody::new_error(StatusCode::FORBIDDEN, message)),
-            )
-                .into_response(),
+            ApiError::ValidationFailed(fields) => {
+                ("request validate error".to_string(), Some(fields))
+            }
+            ApiError::UnprocessableEntity(message)
+            | ApiError::PermissionDenied(message)
+            | ApiError::BadRequestError(message)
+            | ApiError::AuthorizationError(message)
+            | ApiError::NotFound(message)
+            | ApiError::Conflict(message)
+            | ApiError::TooManyRequests(message) => (message, None),
+        };
+        ApiErrorData {
+            code,
+            message,
+            request_id: request_id::current_request_id(),
+            fields,
         }
     }
 }
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+        let data = self.into_data();
+        // Kept on the response so the problem+json middleware can re-render it.
+        let mut response = (
+            status,
+            Json(ApiResponseBody::new_error(status, data.clone())),
+        )
+            .into_response();
+        response.extensions_mut().insert(data);
+        response
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use tokio::time::{self, MissedTickBehavior};
+
+use crate::{
+    config::Settings,
+    domain::blog::{models::posts::PurgeTrashRequest, ports::BlogService},
+};
+
+pub struct Scheduler<BS: BlogService> {
+    blog_service: BS,
+    interval: Duration,
+    trash_retention_days: u32,
+}
+
+impl<BS: BlogService> Scheduler<BS> {
+    pub fn new(blog_service: BS, config: &Settings) -> Self {
+        Self {
+            blog_service,
+            interval: Duration::from_secs(config.scheduler.interval),
+            trash_retention_days: config.trash.retention_days,
+        }
+    }
+
+    pub async fn run(self) {
+        let mut ticker = time::interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            self.publish_due_posts().await;
+            self.purge_trash().await;
+            self.purge_expired_tokens().await;
+        }
+    }
+
+    async fn publish_due_posts(&self) {
+        match self.blog_service.publish_due_posts().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("published {count} scheduled posts"),
+            Err(err) => tracing::error!("failed to publish scheduled posts: {:?}", err),
+        }
+    }
+
+    async fn purge_trash(&self) {
+        let req = PurgeTrashRequest::new(self.trash_retention_days);
+        match self.blog_service.purge_trash(&req).await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("purged {count} posts from the trash"),
+            Err(err) => tracing::error!("failed to purge trash: {:?}", err),
+        }
+    }
+
+    async fn purge_expired_tokens(&self) {
+        match self.blog_service.purge_expired_tokens().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!("purged {count} expired tokens"),
+            Err(err) => tracing::error!("failed to purge expired tokens: {:?}", err),
+        }
+    }
+}
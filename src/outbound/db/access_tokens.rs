@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::domain::blog::models::access_tokens::AccessToken;
+
+use super::postgres::Pg;
+
+impl Pg {
+    pub async fn save_access_token(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        name: &str,
+        token_hash: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<AccessToken> {
+        let id = Uuid::new_v4();
+        let token = sqlx::query_as::<_, AccessToken>(
+            r#"
+            INSERT INTO access_tokens (id, user_id, name, token_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .bind(name.to_string())
+        .bind(token_hash.to_string())
+        .bind(scopes)
+        .bind(expires_at)
+        .fetch_one(tx.as_mut())
+        .await?;
+        Ok(token)
+    }
+
+    pub async fn list_access_tokens_by_user_id(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+    ) -> anyhow::Result<Vec<AccessToken>> {
+        let tokens = sqlx::query_as::<_, AccessToken>(
+            r#"
+            SELECT * FROM access_tokens WHERE user_id = $1 ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_all(tx.as_mut())
+        .await?;
+        Ok(tokens)
+    }
+
+    pub async fn delete_access_token(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        id: &str,
+    ) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"
+            DELETE FROM access_tokens WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    pub async fn delete_access_tokens_by_user_id(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM access_tokens WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    /// Looks up an unexpired token and records that it was just used.
+    pub async fn touch_access_token(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<AccessToken>> {
+        let token = sqlx::query_as::<_, AccessToken>(
+            r#"
+            UPDATE access_tokens SET last_used_at = NOW()
+            WHERE token_hash = $1 AND (expires_at IS NULL OR expires_at > NOW())
+            RETURNING *
+            "#,
+        )
+        .bind(token_hash.to_string())
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(token)
+    }
+}
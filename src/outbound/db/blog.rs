@@ -1,26 +1,60 @@
-use std::vec;
-
 use anyhow::Context;
+use chrono::{Duration, Utc};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
 
 use crate::{
     domain::blog::{
         error::Error,
+        markdown,
         models::{
+            access_tokens::{
+                AccessToken, CreateAccessTokenRequest, ListAccessTokensRequest, NewAccessToken,
+                RevokeAccessTokenRequest, ACCESS_TOKEN_PREFIX,
+            },
+            auth::{
+                ChangePasswordRequest, EmailVerificationToken, ForgotPasswordRequest, LoginAttempt,
+                LoginResponse, LogoutAllRequest, LogoutRequest, PasswordResetToken,
+                RefreshTokenRequest, ResetPasswordRequest, TokenPair, UnlockUserRequest,
+                VerifyEmailRequest,
+            },
+            oidc::{ExternalIdentity, IdentityLoginRequest, OidcLoginState},
+            policies::{
+                ExplainPermissionRequest, GetUserRolesRequest, PermissionExplanation, Policy,
+                PolicyRequest, RoleAssignmentRequest, RoleGrouping, RoleGroupingRequest, UserRoles,
+            },
             posts::{
-                BatchDeletePostRequest, CreatePostRequest, DeletePostRequest, ListPostRequest,
-                ListPostResponse, Post, UpdatePostRequest,
+                BatchDeletePostRequest, ChangePostStatusRequest, CreatePostRequest,
+                DeletePostRequest, GetPostBySlugRequest, GetPostBySlugResponse, GetPostRequest,
+                GetPublishedPostRequest, ListPostRequest, ListPostResponse, ListTrashedPostRequest,
+                Post, PostStatus, PurgeTrashRequest, TrashedPostRequest, UpdatePostRequest,
+            },
+            revisions::{
+                GetPostRevisionRequest, ListPostRevisionsRequest, PostRevision,
+                RestorePostRevisionRequest,
+            },
+            roles::{user_subject, PostAccess, Role, ANY_POST_OBJECT},
+            search::{SearchPostRequest, SearchPostResponse},
+            tags::Tag,
+            two_factor::{
+                ConfirmTotpRequest, DisableTotpRequest, EnrollTotpRequest, TotpEnrollment,
+                VerifyTwoFactorRequest,
             },
             users::{
-                CreateUserRequest, DeleteUserRequest, GetUserByIdRequest, GetUserRequest,
-                LoginRequest, User,
+                is_username_char, CreateUserRequest, DeleteUserRequest, GetUserByIdRequest,
+                GetUserRequest, LoginRequest, User,
             },
         },
         ports::BlogRepository,
     },
-    utils::{jwt, verify_password_hash},
+    utils::{self, token, totp, verify_dummy_password_hash, verify_password_hash},
 };
 
-use super::postgres::Pg;
+use super::{error::DbResultExt, postgres::Pg, two_factor::MAX_CHALLENGE_FAILURES};
+
+const PUBLISH_BATCH_SIZE: u32 = 100;
+/// Numbered suffixes tried when a provisioned username is taken.
+const PROVISION_USERNAME_ATTEMPTS: u32 = 100;
 
 impl BlogRepository for Pg {
     async fn create_post(&self, req: &CreatePostRequest) -> Result<Post, Error> {
@@ -29,10 +63,21 @@ impl BlogRepository for Pg {
             .begin()
             .await
             .context("failed t start transaction")?;
-        let post = self
-            .save_post(&mut tx, &req.title, &req.content, &req.username)
+        let slug = self
+            .unique_slug(&mut tx, &req.username, &req.slug, None)
+            .await?;
+        let mut post = self
+            .save_post(&mut tx, req, &slug)
+            .await
+            .context("failed to save post")
+            .or_conflict("slug already exists")?;
+        self.set_post_tags(&mut tx, &post.id, &req.tags)
             .await
-            .context("failed to save post")?;
+            .context("failed to save post tags")?;
+        post.tags = req.tags.clone();
+        self.save_post_revision(&mut tx, &post)
+            .await
+            .context("failed to save post revision")?;
         tx.commit().await.context("failed to commit")?;
         Ok(post)
     }
@@ -43,24 +88,275 @@ impl BlogRepository for Pg {
             .begin()
             .await
             .context("failed t start transaction")?;
-        let posts = self
-            .list_post(&mut tx, req.offset, req.limit, &req.username)
-            .await?;
-        let total = self.post_count(&mut tx).await?;
+        let mut posts = self.list_post(&mut tx, &req).await?;
+        self.attach_tags(&mut tx, &mut posts).await?;
+        let total = self.post_count(&mut tx, &req).await?;
         tx.commit().await.context("failed to commit")?;
         Ok(ListPostResponse { total, posts })
     }
 
+    async fn search_post(&self, req: &SearchPostRequest) -> Result<SearchPostResponse, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let mut results = self.search_post(&mut tx, req).await?;
+        self.attach_tags(&mut tx, results.iter_mut().map(|r| &mut r.post))
+            .await?;
+        let total = self.search_post_count(&mut tx, req).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(SearchPostResponse { total, results })
+    }
+
+    async fn get_post(&self, req: &GetPostRequest) -> Result<Post, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let owner = self
+            .post_owner(&mut tx, &req.id, &req.username, PostAccess::Read)
+            .await?;
+        let mut post = self
+            .get_post_by_id_and_username(&mut tx, &req.id, &owner)
+            .await?
+            .ok_or_else(|| Error::NotFound("post not found".to_string()))?;
+        self.attach_tags(&mut tx, [&mut post]).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(post)
+    }
+
     async fn update_post(&self, req: &UpdatePostRequest) -> Result<Post, Error> {
         let mut tx = self
             .pool
             .begin()
             .await
             .context("failed t start transaction")?;
+        let owner = self
+            .post_owner(&mut tx, &req.id, &req.username, PostAccess::Write)
+            .await?;
+        let current = self
+            .get_post_by_id_and_username_for_update(&mut tx, &req.id, &owner)
+            .await?
+            .ok_or_else(|| Error::NotFound("post not found".to_string()))?;
+        if req.publish_at.is_some() && current.status != PostStatus::Draft {
+            return Err(Error::Conflict(
+                "only draft posts can be scheduled".to_string(),
+            ));
+        }
+        let slug = self
+            .rename_slug(&mut tx, &current, &owner, &req.slug)
+            .await?;
+        if let Some(tags) = &req.tags {
+            self.set_post_tags(&mut tx, &req.id, tags)
+                .await
+                .context("failed to save post tags")?;
+        }
+        let mut post = self
+            .update_post(&mut tx, req, &owner, &slug)
+            .await
+            .context("failed to update post")
+            .or_not_found("post not found")?;
+        self.save_post_revision(&mut tx, &post)
+            .await
+            .context("failed to save post revision")?;
+        self.attach_tags(&mut tx, [&mut post]).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(post)
+    }
+
+    async fn change_post_status(&self, req: &ChangePostStatusRequest) -> Result<Post, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let owner = self
+            .post_owner(&mut tx, &req.id, &req.username, PostAccess::Write)
+            .await?;
+        let current = self
+            .get_post_by_id_and_username_for_update(&mut tx, &req.id, &owner)
+            .await?
+            .ok_or_else(|| Error::NotFound("post not found".to_string()))?;
+        if !current.status.can_transition_to(req.status) {
+            return Err(Error::Conflict(format!(
+                "cannot change post status from {} to {}",
+                current.status, req.status
+            )));
+        }
+        let mut post = self
+            .update_post_status(&mut tx, &req.id, req.status)
+            .await
+            .context("failed to update post status")?;
+        self.attach_tags(&mut tx, [&mut post]).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(post)
+    }
+
+    async fn publish_due_posts(&self) -> Result<u64, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let posts = self
+            .publish_due_posts(&mut tx, PUBLISH_BATCH_SIZE)
+            .await
+            .context("failed to publish due posts")?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(posts.len() as u64)
+    }
+
+    async fn list_tags(&self) -> Result<Vec<Tag>, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let tags = self.list_tags(&mut tx).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(tags)
+    }
+
+    async fn list_post_revisions(
+        &self,
+        req: &ListPostRevisionsRequest,
+    ) -> Result<Vec<PostRevision>, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let owner = self
+            .post_owner(&mut tx, &req.post_id, &req.username, PostAccess::Read)
+            .await?;
+        self.get_post_by_id_and_username(&mut tx, &req.post_id, &owner)
+            .await?
+            .ok_or_else(|| Error::NotFound("post not found".to_string()))?;
+        let revisions = self.list_post_revisions(&mut tx, &req.post_id).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(revisions)
+    }
+
+    async fn get_post_revision(&self, req: &GetPostRevisionRequest) -> Result<PostRevision, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let owner = self
+            .post_owner(&mut tx, &req.post_id, &req.username, PostAccess::Read)
+            .await?;
+        self.get_post_by_id_and_username(&mut tx, &req.post_id, &owner)
+            .await?
+            .ok_or_else(|| Error::NotFound("post not found".to_string()))?;
+        let revision = self
+            .get_post_revision(&mut tx, &req.post_id, req.revision)
+            .await?
+            .ok_or_else(|| Error::NotFound("revision not found".to_string()))?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(revision)
+    }
+
+    async fn restore_post_revision(&self, req: &RestorePostRevisionRequest) -> Result<Post, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let owner = self
+            .post_owner(&mut tx, &req.post_id, &req.username, PostAccess::Write)
+            .await?;
+        let current = self
+            .get_post_by_id_and_username_for_update(&mut tx, &req.post_id, &owner)
+            .await?
+            .ok_or_else(|| Error::NotFound("post not found".to_string()))?;
+        let revision = self
+            .get_post_revision(&mut tx, &req.post_id, req.revision)
+            .await?
+            .ok_or_else(|| Error::NotFound("revision not found".to_string()))?;
+        let slug = self
+            .rename_slug(&mut tx, &current, &owner, &utils::slugify(&revision.title))
+            .await?;
+        let mut post = self
+            .update_post_content(
+                &mut tx,
+                &req.post_id,
+                &revision.title,
+                &slug,
+                &revision.content,
+                &markdown::render_markdown(&revision.content),
+            )
+            .await
+            .context("failed to restore post revision")?;
+        self.save_post_revision(&mut tx, &post)
+            .await
+            .context("failed to save post revision")?;
+        self.attach_tags(&mut tx, [&mut post]).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(post)
+    }
+
+    async fn get_post_by_slug(
+        &self,
+        req: &GetPostBySlugRequest,
+    ) -> Result<GetPostBySlugResponse, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let status = match &req.viewer {
+            Some(viewer) if viewer == &req.username => req.status,
+            Some(viewer)
+                if self
+                    .enforcer
+                    .check_permission(
+                        &user_subject(viewer),
+                        ANY_POST_OBJECT,
+                        PostAccess::Read.as_str(),
+                    )
+                    .await? =>
+            {
+                req.status
+            }
+            _ => Some(PostStatus::Published),
+        };
         let post = self
-            .update_post(&mut tx, &req.id, &req.title, &req.content, &req.username)
+            .get_post_by_slug(&mut tx, &req.username, &req.slug, status)
+            .await?;
+        let res = match post {
+            Some(mut post) => {
+                self.attach_tags(&mut tx, [&mut post]).await?;
+                GetPostBySlugResponse::Found(post)
+            }
+            None => {
+                let slug = self
+                    .get_current_slug_by_old_slug(&mut tx, &req.username, &req.slug, status)
+                    .await?
+                    .ok_or_else(|| Error::NotFound("post not found".to_string()))?;
+                GetPostBySlugResponse::Moved {
+                    username: req.username.clone(),
+                    slug,
+                }
+            }
+        };
+        tx.commit().await.context("failed to commit")?;
+        Ok(res)
+    }
+
+    async fn get_published_post(&self, req: &GetPublishedPostRequest) -> Result<Post, Error> {
+        let mut tx = self
+            .pool
+            .begin()
             .await
-            .context("failed to update post")?;
+            .context("failed t start transaction")?;
+        let mut post = self
+            .get_published_post(&mut tx, &req.id)
+            .await?
+            .ok_or_else(|| Error::NotFound("post not found".to_string()))?;
+        self.attach_tags(&mut tx, [&mut post]).await?;
         tx.commit().await.context("failed to commit")?;
         Ok(post)
     }
@@ -71,8 +367,15 @@ impl BlogRepository for Pg {
             .begin()
             .await
             .context("failed t start transaction")?;
-        self.delete_post_by_id_and_username(&mut tx, &req.id, &req.username)
+        let owner = self
+            .post_owner(&mut tx, &req.id, &req.username, PostAccess::Write)
+            .await?;
+        let trashed = self
+            .trash_post_by_id_and_username(&mut tx, &req.id, &owner)
             .await?;
+        if !trashed {
+            return Err(Error::NotFound("post not found".to_string()));
+        }
         tx.commit().await.context("failed to commit")?;
         Ok(())
     }
@@ -84,12 +387,109 @@ impl BlogRepository for Pg {
             .await
             .context("failed t start transaction")?;
 
-        self.delete_posts_by_ids(&mut tx, req.ids.clone(), &req.username)
+        // Without a role that can write every post, only the caller's own
+        // posts are trashed and the other ids are ignored.
+        let any_author = self
+            .enforcer
+            .check_permission(
+                &user_subject(&req.username),
+                ANY_POST_OBJECT,
+                PostAccess::Write.as_str(),
+            )
+            .await?;
+        let author = (!any_author).then_some(req.username.as_str());
+        self.trash_posts_by_ids(&mut tx, req.ids.clone(), author)
+            .await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(())
+    }
+
+    async fn list_trashed_posts(
+        &self,
+        req: &ListTrashedPostRequest,
+    ) -> Result<ListPostResponse, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let author = match &req.author {
+            Some(author) if *author != req.username => {
+                let allowed = self
+                    .enforcer
+                    .check_permission(
+                        &user_subject(&req.username),
+                        ANY_POST_OBJECT,
+                        PostAccess::Read.as_str(),
+                    )
+                    .await?;
+                if !allowed {
+                    return Err(Error::Forbidden(
+                        "cannot list the trash of other authors".to_string(),
+                    ));
+                }
+                author
+            }
+            _ => &req.username,
+        };
+        let mut posts = self
+            .list_trashed_posts(&mut tx, req.offset, req.limit, author)
+            .await?;
+        self.attach_tags(&mut tx, &mut posts).await?;
+        let total = self.trashed_post_count(&mut tx, author).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(ListPostResponse { total, posts })
+    }
+
+    async fn restore_trashed_post(&self, req: &TrashedPostRequest) -> Result<Post, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let owner = self
+            .post_owner(&mut tx, &req.id, &req.username, PostAccess::Write)
+            .await?;
+        let mut post = self
+            .restore_trashed_post(&mut tx, &req.id, &owner)
+            .await?
+            .ok_or_else(|| Error::NotFound("post not found in trash".to_string()))?;
+        self.attach_tags(&mut tx, [&mut post]).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(post)
+    }
+
+    async fn delete_trashed_post(&self, req: &TrashedPostRequest) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let owner = self
+            .post_owner(&mut tx, &req.id, &req.username, PostAccess::Write)
             .await?;
+        let deleted = self.delete_trashed_post(&mut tx, &req.id, &owner).await?;
+        if !deleted {
+            return Err(Error::NotFound("post not found in trash".to_string()));
+        }
         tx.commit().await.context("failed to commit")?;
         Ok(())
     }
 
+    async fn purge_trash(&self, req: &PurgeTrashRequest) -> Result<u64, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let count = self
+            .purge_trashed_posts(&mut tx, req.deleted_before)
+            .await
+            .context("failed to purge trash")?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(count)
+    }
+
     async fn create_user(&self, req: &CreateUserRequest) -> Result<User, Error> {
         let mut tx = self
             .pool
@@ -98,7 +498,7 @@ impl BlogRepository for Pg {
             .context("failed t start transaction")?;
         let res = self.get_user_by_username(&mut tx, &req.username).await?;
         if res.is_some() {
-            return Err(Error::Custom("username already exists".to_string()));
+            return Err(Error::Conflict("username already exists".to_string()));
         }
         let user = self
             .save_user(
@@ -109,18 +509,13 @@ impl BlogRepository for Pg {
                 &req.password,
             )
             .await
-            .context("failed to save user")?;
-        self.enforcer
-            .add_policy(
-                "p",
-                vec![
-                    user.username.clone(),
-                    format!("/api/users/{}", user.username),
-                    "(GET)|(POST)|(PUT)|(DELETE)".to_string(),
-                ],
-            )
-            .await?;
+            .context("failed to save user")
+            .or_conflict("username already exists")?;
+        self.save_user_policy(&mut tx, &user.username, req.role)
+            .await
+            .context("failed to save user policy")?;
         tx.commit().await.context("failed to commit")?;
+        self.load_user_policy(&user.username, req.role).await;
         Ok(user)
     }
 
@@ -134,7 +529,7 @@ impl BlogRepository for Pg {
         tx.commit().await.context("failed to commit")?;
         match res {
             Some(user) => Ok(user),
-            None => Err(Error::Custom("user not found".to_string())),
+            None => Err(Error::NotFound("user not found".to_string())),
         }
     }
 
@@ -148,7 +543,7 @@ impl BlogRepository for Pg {
         tx.commit().await.context("failed to commit")?;
         match res {
             Some(user) => Ok(user),
-            None => Err(Error::Custom("user not found".to_string())),
+            None => Err(Error::NotFound("user not found".to_string())),
         }
     }
 
@@ -161,41 +556,898 @@ impl BlogRepository for Pg {
         let user = self.get_user_by_username(&mut tx, &req.username).await?;
         if let Some(user) = user {
             self.delete_user_by_id(&mut tx, &user.id).await?;
-            self.remove_policy(
-                "p",
-                vec![
-                    user.username,
-                    format!("/api/users/{}", req.username),
-                    "(GET)|(POST)|(PUT)|(DELETE)".to_string(),
-                ],
-            )
-            .await?;
+            self.delete_user_policy(&mut tx, &user.username)
+                .await
+                .context("failed to delete user policy")?;
             tx.commit().await.context("failed to commit")?;
+            self.unload_user_policy(&user.username).await;
             Ok(())
         } else {
-            Err(Error::Custom("user not found".to_string()))
+            Err(Error::NotFound("user not found".to_string()))
         }
     }
 
-    async fn login(&self, req: &LoginRequest) -> Result<String, Error> {
+    async fn login(&self, req: &LoginRequest) -> Result<LoginResponse, Error> {
         let mut tx = self
             .pool
             .begin()
             .await
             .context("failed t start transaction")?;
+        let now = Utc::now();
+        let throttle = &req.policy.throttle;
+        let user_key = LoginAttempt::username_key(&req.username);
+        let mut user_attempt = self
+            .get_login_attempt_for_update(&mut tx, &user_key)
+            .await?
+            .unwrap_or_else(|| LoginAttempt::new(user_key));
+        let mut ip_attempt = match &req.ip {
+            Some(ip) => {
+                let ip_key = LoginAttempt::ip_key(ip);
+                let attempt = self.get_login_attempt_for_update(&mut tx, &ip_key).await?;
+                Some(attempt.unwrap_or_else(|| LoginAttempt::new(ip_key)))
+            }
+            None => None,
+        };
+        if user_attempt.is_locked(now)
+            || user_attempt.is_backing_off(throttle, now)
+            || ip_attempt
+                .as_ref()
+                .is_some_and(|a| a.is_locked(now) || a.is_backing_off(throttle, now))
+        {
+            return Err(Error::TooManyRequests(
+                "too many failed login attempts, try again later".to_string(),
+            ));
+        }
+
+        // Unknown users and wrong passwords must look and take the same.
         let user = self.get_user_by_username(&mut tx, &req.username).await?;
-        if let Some(user) = user {
-            verify_password_hash(user.password.clone(), req.password.clone())?;
-            let token =
-                jwt::JWT::new(&req.jwt_secret).generate_token(&req.expiration, user.id, None)?;
-            Ok(token)
+        let user = match user {
+            Some(user) => verify_password_hash(user.password.clone(), req.password.clone())
+                .is_ok()
+                .then_some(user),
+            None => {
+                verify_dummy_password_hash(req.password.clone());
+                None
+            }
+        };
+        let Some(user) = user else {
+            user_attempt.record_failure(throttle.max_failures, throttle.lockout_duration, now);
+            self.save_login_attempt(&mut tx, &user_attempt).await?;
+            if let Some(ip_attempt) = ip_attempt.as_mut() {
+                ip_attempt.record_failure(throttle.ip_max_failures, throttle.lockout_duration, now);
+                self.save_login_attempt(&mut tx, ip_attempt).await?;
+            }
+            tx.commit().await.context("failed to commit")?;
+            return Err(Error::Unauthorized(
+                "invalid username or password".to_string(),
+            ));
+        };
+        user.ensure_may_sign_in(req.policy.require_verified_email)?;
+        // With 2FA the password alone proves little; failures are only
+        // cleared once the second factor is verified too.
+        if user.is_two_factor_enabled() {
+            let challenge = self
+                .issue_login_challenge(&mut tx, &user.id, req.policy.challenge_expiration)
+                .await?;
+            tx.commit().await.context("failed to commit")?;
+            return Ok(challenge);
+        }
+        self.clear_login_attempt(&mut tx, &user_attempt.key).await?;
+        let family_id = Uuid::new_v4().to_string();
+        let tokens = self
+            .issue_token_pair(
+                &mut tx,
+                &user.id,
+                &family_id,
+                &req.jwt,
+                req.expiration,
+                req.refresh_expiration,
+            )
+            .await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(LoginResponse::Authenticated(tokens))
+    }
+
+    async fn verify_two_factor(&self, req: &VerifyTwoFactorRequest) -> Result<TokenPair, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let invalid = || Error::Unauthorized("invalid or expired login challenge".to_string());
+        let challenge = self
+            .get_login_challenge_by_hash_for_update(
+                &mut tx,
+                &token::hash_token(&req.challenge_token),
+            )
+            .await?
+            .ok_or_else(invalid)?;
+        if challenge.used_at.is_some()
+            || challenge.expires_at <= Utc::now()
+            || challenge.failures >= MAX_CHALLENGE_FAILURES
+        {
+            return Err(invalid());
+        }
+        let user = self
+            .find_user_by_id(&mut tx, &challenge.user_id)
+            .await?
+            .ok_or_else(invalid)?;
+        user.ensure_may_sign_in(req.policy.require_verified_email)?;
+        if !self
+            .verify_throttled_second_factor(&mut tx, &user, &req.code, &req.policy.throttle)
+            .await?
+        {
+            self.record_login_challenge_failure(&mut tx, &challenge.id)
+                .await?;
+            tx.commit().await.context("failed to commit")?;
+            return Err(Error::Unauthorized("invalid two-factor code".to_string()));
+        }
+        self.mark_login_challenge_used(&mut tx, &challenge.id)
+            .await?;
+        let family_id = Uuid::new_v4().to_string();
+        let tokens = self
+            .issue_token_pair(
+                &mut tx,
+                &user.id,
+                &family_id,
+                &req.jwt,
+                req.expiration,
+                req.refresh_expiration,
+            )
+            .await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(tokens)
+    }
+
+    async fn enroll_totp(&self, req: &EnrollTotpRequest) -> Result<TotpEnrollment, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let user = self
+            .find_user_by_id(&mut tx, &req.user_id)
+            .await?
+            .ok_or_else(|| Error::NotFound("user not found".to_string()))?;
+        if user.is_two_factor_enabled() {
+            return Err(Error::Conflict(
+                "two-factor authentication is already enabled".to_string(),
+            ));
+        }
+        let secret = totp::generate_secret();
+        self.set_totp_secret(&mut tx, &user.id, &secret).await?;
+        tx.commit().await.context("failed to commit")?;
+        let account = user.email.as_deref().unwrap_or(&user.username);
+        Ok(TotpEnrollment {
+            otpauth_uri: totp::otpauth_uri(&req.issuer, account, &secret),
+            secret,
+        })
+    }
+
+    async fn confirm_totp(&self, req: &ConfirmTotpRequest) -> Result<Vec<String>, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let user = self
+            .find_user_by_id(&mut tx, &req.user_id)
+            .await?
+            .ok_or_else(|| Error::NotFound("user not found".to_string()))?;
+        if user.is_two_factor_enabled() {
+            return Err(Error::Conflict(
+                "two-factor authentication is already enabled".to_string(),
+            ));
+        }
+        let secret = user.totp_secret.as_ref().ok_or_else(|| {
+            Error::Conflict("two-factor authentication was not enrolled".to_string())
+        })?;
+        let step = totp::verify(secret, req.code.trim(), Utc::now().timestamp() as u64)
+            .ok_or_else(|| Error::Unauthorized("invalid two-factor code".to_string()))?;
+        self.enable_totp(&mut tx, &user.id, step).await?;
+        let codes: Vec<String> = (0..req.recovery_codes)
+            .map(|_| totp::generate_recovery_code())
+            .collect();
+        let code_hashes = codes
+            .iter()
+            .map(|code| utils::compute_password_hash(&totp::normalize_recovery_code(code)))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.replace_recovery_codes(&mut tx, &user.id, &code_hashes)
+            .await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(codes)
+    }
+
+    async fn disable_totp(&self, req: &DisableTotpRequest) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let user = self
+            .find_user_by_id(&mut tx, &req.user_id)
+            .await?
+            .ok_or_else(|| Error::NotFound("user not found".to_string()))?;
+        if !user.is_two_factor_enabled() {
+            return Err(Error::Conflict(
+                "two-factor authentication is not enabled".to_string(),
+            ));
+        }
+        if !self
+            .verify_throttled_second_factor(&mut tx, &user, &req.code, &req.throttle)
+            .await?
+        {
+            tx.commit().await.context("failed to commit")?;
+            return Err(Error::Unauthorized("invalid two-factor code".to_string()));
+        }
+        self.clear_totp(&mut tx, &user.id).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(())
+    }
+
+    async fn unlock_user(&self, req: &UnlockUserRequest) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        self.get_user_by_username(&mut tx, &req.username)
+            .await?
+            .ok_or_else(|| Error::NotFound("user not found".to_string()))?;
+        self.clear_login_attempt(&mut tx, &LoginAttempt::username_key(&req.username))
+            .await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(())
+    }
+
+    async fn refresh_token(&self, req: &RefreshTokenRequest) -> Result<TokenPair, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let current = self
+            .get_refresh_token_by_hash_for_update(&mut tx, &token::hash_token(&req.refresh_token))
+            .await?
+            .ok_or_else(|| Error::Unauthorized("invalid refresh token".to_string()))?;
+        if current.revoked_at.is_some() {
+            return Err(Error::Unauthorized("refresh token revoked".to_string()));
+        }
+        if current.used_at.is_some() {
+            // A rotated token showing up again means it leaked: cut off the
+            // whole family, including whoever holds the newest token.
+            self.revoke_refresh_token_family(&mut tx, &current.family_id)
+                .await?;
+            tx.commit().await.context("failed to commit")?;
+            return Err(Error::Unauthorized(
+                "refresh token reuse detected".to_string(),
+            ));
+        }
+        if current.expires_at <= Utc::now() {
+            return Err(Error::Unauthorized("refresh token expired".to_string()));
+        }
+        self.find_user_by_id(&mut tx, &current.user_id)
+            .await?
+            .ok_or_else(|| Error::Unauthorized("invalid refresh token".to_string()))?
+            .ensure_may_sign_in(req.require_verified_email)?;
+        self.mark_refresh_token_used(&mut tx, &current.id).await?;
+        let tokens = self
+            .issue_token_pair(
+                &mut tx,
+                &current.user_id,
+                &current.family_id,
+                &req.jwt,
+                req.expiration,
+                req.refresh_expiration,
+            )
+            .await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(tokens)
+    }
+
+    async fn logout(&self, req: &LogoutRequest) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        self.revoke_token(&mut tx, &req.jti, &req.user_id, req.expires_at)
+            .await?;
+        if let Some(refresh_token) = &req.refresh_token {
+            let current = self
+                .get_refresh_token_by_hash_for_update(&mut tx, &token::hash_token(refresh_token))
+                .await?;
+            if let Some(current) = current.filter(|t| t.user_id == req.user_id) {
+                self.revoke_refresh_token_family(&mut tx, &current.family_id)
+                    .await?;
+            }
+        }
+        tx.commit().await.context("failed to commit")?;
+        self.revocations.insert(&req.jti);
+        Ok(())
+    }
+
+    async fn logout_all(&self, req: &LogoutAllRequest) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        self.revoke_user_tokens(&mut tx, &req.user_id).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(())
+    }
+
+    async fn is_token_revoked(&self, jti: &str) -> Result<bool, Error> {
+        if self.revocations.is_revoked(jti) {
+            return Ok(true);
+        }
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let revoked = self.token_revoked(&mut tx, jti).await?;
+        tx.commit().await.context("failed to commit")?;
+        if revoked {
+            self.revocations.insert(jti);
+        }
+        Ok(revoked)
+    }
+
+    async fn purge_expired_tokens(&self) -> Result<u64, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let count = self
+            .purge_expired_tokens(&mut tx)
+            .await
+            .context("failed to purge expired tokens")?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(count)
+    }
+
+    async fn change_password(&self, req: &ChangePasswordRequest) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let user = self
+            .find_user_by_id(&mut tx, &req.user_id)
+            .await?
+            .ok_or_else(|| Error::NotFound("user not found".to_string()))?;
+        verify_password_hash(user.password, req.current_password.clone())
+            .map_err(|_| Error::Unauthorized("current password is incorrect".to_string()))?;
+        self.update_user_password(&mut tx, &user.id, &req.new_password)
+            .await?;
+        self.revoke_user_tokens(&mut tx, &user.id).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(())
+    }
+
+    async fn create_password_reset_tokens(
+        &self,
+        req: &ForgotPasswordRequest,
+    ) -> Result<Vec<PasswordResetToken>, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let users = self.get_users_by_email(&mut tx, &req.email).await?;
+        let expires_at = Utc::now() + Duration::seconds(req.expiration as i64);
+        let mut tokens = Vec::with_capacity(users.len());
+        for user in users {
+            let reset_token = token::generate_token();
+            self.save_password_reset_token(
+                &mut tx,
+                &user.id,
+                &token::hash_token(&reset_token),
+                expires_at,
+            )
+            .await?;
+            tokens.push(PasswordResetToken {
+                username: user.username,
+                email: user.email.unwrap_or_default(),
+                token: reset_token,
+            });
+        }
+        tx.commit().await.context("failed to commit")?;
+        Ok(tokens)
+    }
+
+    async fn reset_password(&self, req: &ResetPasswordRequest) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let user_id = self
+            .use_password_reset_token(&mut tx, &token::hash_token(&req.token))
+            .await?
+            .ok_or_else(|| Error::Unauthorized("invalid or expired reset token".to_string()))?;
+        self.update_user_password(&mut tx, &user_id, &req.new_password)
+            .await?;
+        self.revoke_user_tokens(&mut tx, &user_id).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(())
+    }
+
+    async fn create_email_verification_token(
+        &self,
+        user_id: &str,
+        expiration: u64,
+    ) -> Result<Option<EmailVerificationToken>, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let user = self
+            .find_user_by_id(&mut tx, user_id)
+            .await?
+            .ok_or_else(|| Error::NotFound("user not found".to_string()))?;
+        let Some(email) = user.email.clone().filter(|_| !user.is_email_verified()) else {
+            return Ok(None);
+        };
+        let verification_token = token::generate_token();
+        let expires_at = Utc::now() + Duration::seconds(expiration as i64);
+        self.save_email_verification_token(
+            &mut tx,
+            &user.id,
+            &token::hash_token(&verification_token),
+            expires_at,
+        )
+        .await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(Some(EmailVerificationToken {
+            username: user.username,
+            email,
+            token: verification_token,
+        }))
+    }
+
+    async fn verify_email(&self, req: &VerifyEmailRequest) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let user_id = self
+            .use_email_verification_token(&mut tx, &token::hash_token(&req.token))
+            .await?
+            .ok_or_else(|| {
+                Error::Unauthorized("invalid or expired verification token".to_string())
+            })?;
+        self.mark_email_verified(&mut tx, &user_id).await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(())
+    }
+
+    async fn create_access_token(
+        &self,
+        req: &CreateAccessTokenRequest,
+    ) -> Result<NewAccessToken, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let secret = format!("{ACCESS_TOKEN_PREFIX}{}", token::generate_token());
+        let scopes: Vec<String> = req
+            .scopes
+            .iter()
+            .map(|scope| scope.as_str().to_string())
+            .collect();
+        let access_token = self
+            .save_access_token(
+                &mut tx,
+                &req.user_id,
+                &req.name,
+                &token::hash_token(&secret),
+                &scopes,
+                req.expires_at,
+            )
+            .await
+            .context("failed to save access token")?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(NewAccessToken {
+            token: access_token,
+            secret,
+        })
+    }
+
+    async fn list_access_tokens(
+        &self,
+        req: &ListAccessTokensRequest,
+    ) -> Result<Vec<AccessToken>, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let tokens = self
+            .list_access_tokens_by_user_id(&mut tx, &req.user_id)
+            .await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(tokens)
+    }
+
+    async fn revoke_access_token(&self, req: &RevokeAccessTokenRequest) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let deleted = self
+            .delete_access_token(&mut tx, &req.user_id, &req.id)
+            .await?;
+        if !deleted {
+            return Err(Error::NotFound("access token not found".to_string()));
+        }
+        tx.commit().await.context("failed to commit")?;
+        Ok(())
+    }
+
+    async fn authenticate_access_token(&self, token: &str) -> Result<AccessToken, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let access_token = self
+            .touch_access_token(&mut tx, &token::hash_token(token))
+            .await?
+            .ok_or_else(|| Error::Unauthorized("invalid or expired access token".to_string()))?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(access_token)
+    }
+
+    async fn save_oidc_login_state(
+        &self,
+        state: &OidcLoginState,
+        expiration: u64,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let expires_at = Utc::now() + Duration::seconds(expiration as i64);
+        self.save_oidc_login_state(&mut tx, &token::hash_token(&state.state), state, expires_at)
+            .await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(())
+    }
+
+    async fn take_oidc_login_state(&self, state: &str) -> Result<Option<OidcLoginState>, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let res = self
+            .delete_oidc_login_state(&mut tx, &token::hash_token(state))
+            .await?;
+        tx.commit().await.context("failed to commit")?;
+        Ok(res.map(|(nonce, code_verifier)| OidcLoginState {
+            state: state.to_string(),
+            nonce,
+            code_verifier,
+        }))
+    }
+
+    async fn login_with_identity(
+        &self,
+        req: &IdentityLoginRequest,
+    ) -> Result<LoginResponse, Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let identity = &req.identity;
+        let linked = self
+            .get_user_by_identity(&mut tx, &identity.issuer, &identity.subject)
+            .await?;
+        let mut provisioned = false;
+        let user = match linked {
+            Some(user) => user,
+            None => {
+                let user = match self.find_linkable_user(&mut tx, identity).await? {
+                    Some(user) => user,
+                    None if req.auto_provision => {
+                        provisioned = true;
+                        self.provision_user(&mut tx, identity, req.default_role)
+                            .await?
+                    }
+                    None => {
+                        return Err(Error::Forbidden(
+                            "no account is linked to this identity".to_string(),
+                        ))
+                    }
+                };
+                self.save_user_identity(&mut tx, &user.id, identity)
+                    .await
+                    .context("failed to link identity")
+                    .or_conflict("identity is already linked")?;
+                user
+            }
+        };
+        user.ensure_may_sign_in(req.policy.require_verified_email)?;
+        // The provider only stands in for the password; a second factor is
+        // still asked for.
+        let res = if user.is_two_factor_enabled() {
+            self.issue_login_challenge(&mut tx, &user.id, req.policy.challenge_expiration)
+                .await?
         } else {
-            Err(Error::Custom("user not found".to_string()))
+            let family_id = Uuid::new_v4().to_string();
+            let tokens = self
+                .issue_token_pair(
+                    &mut tx,
+                    &user.id,
+                    &family_id,
+                    &req.jwt,
+                    req.expiration,
+                    req.refresh_expiration,
+                )
+                .await?;
+            LoginResponse::Authenticated(tokens)
+        };
+        tx.commit().await.context("failed to commit")?;
+        if provisioned {
+            self.load_user_policy(&user.username, req.default_role)
+                .await;
         }
+        Ok(res)
     }
 
     async fn check_permission(&self, sub: &str, obj: &str, act: &str) -> Result<bool, Error> {
         let res = self.enforcer.check_permission(sub, obj, act).await?;
         Ok(res)
     }
+
+    async fn list_policies(&self) -> Result<Vec<Policy>, Error> {
+        let policies = self.enforcer.policies("p").await;
+        Ok(policies.into_iter().map(Policy::from).collect())
+    }
+
+    async fn create_policy(&self, req: &PolicyRequest) -> Result<Policy, Error> {
+        self.save_committed_rule("p", req.rule())
+            .await?
+            .then(|| Policy::from(req.rule()))
+            .ok_or_else(|| Error::Conflict("policy already exists".to_string()))
+    }
+
+    async fn delete_policy(&self, req: &PolicyRequest) -> Result<(), Error> {
+        self.delete_committed_rule("p", req.rule())
+            .await?
+            .then_some(())
+            .ok_or_else(|| Error::NotFound("policy not found".to_string()))
+    }
+
+    async fn list_role_groupings(&self) -> Result<Vec<RoleGrouping>, Error> {
+        let groupings = self.enforcer.grouping_policies("g").await;
+        Ok(groupings.into_iter().map(RoleGrouping::from).collect())
+    }
+
+    async fn create_role_grouping(&self, req: &RoleGroupingRequest) -> Result<RoleGrouping, Error> {
+        self.save_committed_rule("g", req.rule())
+            .await?
+            .then(|| RoleGrouping::from(req.rule()))
+            .ok_or_else(|| Error::Conflict("role grouping already exists".to_string()))
+    }
+
+    async fn delete_role_grouping(&self, req: &RoleGroupingRequest) -> Result<(), Error> {
+        self.delete_committed_rule("g", req.rule())
+            .await?
+            .then_some(())
+            .ok_or_else(|| Error::NotFound("role grouping not found".to_string()))
+    }
+
+    async fn get_user_roles(&self, req: &GetUserRolesRequest) -> Result<UserRoles, Error> {
+        self.ensure_user_exists(&req.username).await?;
+        let subject = user_subject(&req.username);
+        let (roles, implicit_roles) = self.enforcer.roles_for_user(&subject).await;
+        Ok(UserRoles {
+            username: req.username.clone(),
+            roles,
+            implicit_roles,
+        })
+    }
+
+    async fn assign_role(&self, req: &RoleAssignmentRequest) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        // Holding the user row keeps a concurrent delete_user from missing
+        // the new rule and leaving it orphaned.
+        self.get_user_by_username_for_update(&mut tx, &req.username)
+            .await?
+            .ok_or_else(|| Error::NotFound("user not found".to_string()))?;
+        let rule = vec![user_subject(&req.username), req.role.subject()];
+        self.save_casbin_rule(&mut tx, "g", &rule)
+            .await
+            .context("failed to save role")?;
+        tx.commit().await.context("failed to commit")?;
+        // The role is granted either way; see `load_user_policy`.
+        if let Err(e) = self.enforcer.load_committed_policy("g", rule).await {
+            tracing::error!("failed to load the role of user {}: {:?}", req.username, e);
+        }
+        Ok(())
+    }
+
+    async fn revoke_role(&self, req: &RoleAssignmentRequest) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        self.get_user_by_username_for_update(&mut tx, &req.username)
+            .await?
+            .ok_or_else(|| Error::NotFound("user not found".to_string()))?;
+        let rule = vec![user_subject(&req.username), req.role.subject()];
+        let removed = self
+            .delete_casbin_rule(&mut tx, "g", &rule)
+            .await
+            .context("failed to delete role")?;
+        if !removed {
+            return Err(Error::NotFound(format!(
+                "user does not have the {} role",
+                req.role
+            )));
+        }
+        tx.commit().await.context("failed to commit")?;
+        // The role is revoked either way; see `load_user_policy`.
+        if let Err(e) = self.enforcer.unload_committed_policy("g", rule).await {
+            tracing::error!(
+                "failed to unload the role of user {}: {:?}",
+                req.username,
+                e
+            );
+        }
+        Ok(())
+    }
+
+    async fn explain_permission(
+        &self,
+        req: &ExplainPermissionRequest,
+    ) -> Result<PermissionExplanation, Error> {
+        let (allowed, rule) = self.enforcer.explain(&req.sub, &req.obj, &req.act).await?;
+        let (_, roles) = self.enforcer.roles_for_user(&req.sub).await;
+        Ok(PermissionExplanation {
+            allowed,
+            policy: rule.map(Policy::from),
+            roles,
+        })
+    }
+}
+
+impl Pg {
+    async fn ensure_user_exists(&self, username: &str) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        let user = self.get_user_by_username(&mut tx, username).await?;
+        tx.commit().await.context("failed to commit")?;
+        user.map(|_| ())
+            .ok_or_else(|| Error::NotFound("user not found".to_string()))
+    }
+
+    /// The username to look post `id` up under on behalf of `username`: the
+    /// post's author when that is them or a role lets them `access` every
+    /// post, else `username` itself, so the lookup finds nothing and other
+    /// authors' posts stay indistinguishable from missing ones.
+    async fn post_owner(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        username: &str,
+        access: PostAccess,
+    ) -> Result<String, Error> {
+        let author = self.get_post_author(tx, id).await?;
+        match author {
+            Some(author) if author == username => Ok(author),
+            Some(author)
+                if self
+                    .enforcer
+                    .check_permission(&user_subject(username), ANY_POST_OBJECT, access.as_str())
+                    .await? =>
+            {
+                Ok(author)
+            }
+            _ => Ok(username.to_string()),
+        }
+    }
+
+    /// The only user owning the identity's email, when both sides verified
+    /// it. Unverified addresses are never linked, or anyone could register
+    /// someone else's address and wait for them to sign in.
+    async fn find_linkable_user(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        identity: &ExternalIdentity,
+    ) -> Result<Option<User>, Error> {
+        let Some(email) = identity.email.as_ref().filter(|_| identity.email_verified) else {
+            return Ok(None);
+        };
+        let mut users: Vec<User> = self
+            .get_users_by_email(tx, email)
+            .await?
+            .into_iter()
+            .filter(|user| user.is_email_verified())
+            .collect();
+        if users.len() != 1 {
+            return Ok(None);
+        }
+        Ok(users.pop())
+    }
+
+    /// Creates a user with `role` for the identity, with a username derived
+    /// from it and a random password, so the account can only sign in
+    /// through the provider until a password is reset. The user's policy is
+    /// written in `tx` and must be loaded with `load_user_policy` after commit.
+    async fn provision_user(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        identity: &ExternalIdentity,
+        role: Role,
+    ) -> Result<User, Error> {
+        let base = provisioned_username(identity);
+        let mut username = None;
+        for n in 0..PROVISION_USERNAME_ATTEMPTS {
+            let candidate = match n {
+                0 => base.clone(),
+                n => format!("{base}{n}"),
+            };
+            if self.get_user_by_username(tx, &candidate).await?.is_none() {
+                username = Some(candidate);
+                break;
+            }
+        }
+        let username = username
+            .ok_or_else(|| Error::Conflict("no free username for this identity".to_string()))?;
+        let password = utils::compute_password_hash(&token::generate_token())?;
+        let email = identity.email.clone();
+        let mut user = self
+            .save_user(tx, &username, &email, &None, &password)
+            .await
+            .context("failed to save user")
+            .or_conflict("username already exists")?;
+        if identity.email_verified && email.is_some() {
+            self.mark_email_verified(tx, &user.id).await?;
+            user.email_verified_at = Some(Utc::now());
+        }
+        self.save_user_policy(tx, &user.username, role)
+            .await
+            .context("failed to save user policy")?;
+        Ok(user)
+    }
+}
+
+/// `preferred_username`, else the local part of the email, without the
+/// characters usernames do not allow.
+fn provisioned_username(identity: &ExternalIdentity) -> String {
+    let source = identity
+        .preferred_username
+        .as_deref()
+        .or_else(|| identity.email.as_deref().and_then(|e| e.split('@').next()))
+        .unwrap_or_default();
+    let username: String = source
+        .chars()
+        .filter(|c| is_username_char(*c))
+        .take(40)
+        .collect();
+    if username.is_empty() {
+        "user".to_string()
+    } else {
+        username
+    }
 }
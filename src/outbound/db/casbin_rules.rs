@@ -0,0 +1,137 @@
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+
+use super::postgres::Pg;
+
+/// Columns `casbin_rule` has for rule values; unused ones hold `''`, as the
+/// adapter writes them.
+const RULE_COLUMNS: usize = 6;
+
+impl Pg {
+    /// Writes a rule inside `tx` instead of through the adapter's own pool,
+    /// so it commits or rolls back with the rest of the transaction. The
+    /// enforcer only sees it once it is loaded with
+    /// `EnforcerWrapper::load_committed_policy`. Returns whether the rule
+    /// was new.
+    pub async fn save_casbin_rule(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        ptype: &str,
+        rule: &[String],
+    ) -> anyhow::Result<bool> {
+        let values = rule_values(rule);
+        let result = sqlx::query(
+            r#"
+            INSERT INTO casbin_rule (ptype, v0, v1, v2, v3, v4, v5)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(ptype.to_string())
+        .bind(&values[0])
+        .bind(&values[1])
+        .bind(&values[2])
+        .bind(&values[3])
+        .bind(&values[4])
+        .bind(&values[5])
+        .execute(tx.as_mut())
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Deletes a rule inside `tx`, the counterpart of `save_casbin_rule`;
+    /// `EnforcerWrapper::unload_committed_policy` drops it from the enforcer
+    /// once `tx` commits. Returns whether the rule existed.
+    pub async fn delete_casbin_rule(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        ptype: &str,
+        rule: &[String],
+    ) -> anyhow::Result<bool> {
+        let values = rule_values(rule);
+        let result = sqlx::query(
+            r#"
+            DELETE FROM casbin_rule
+            WHERE ptype = $1 AND v0 = $2 AND v1 = $3 AND v2 = $4 AND v3 = $5 AND v4 = $6 AND v5 = $7
+            "#,
+        )
+        .bind(ptype.to_string())
+        .bind(&values[0])
+        .bind(&values[1])
+        .bind(&values[2])
+        .bind(&values[3])
+        .bind(&values[4])
+        .bind(&values[5])
+        .execute(tx.as_mut())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Saves a rule in a transaction of its own and, once committed, loads
+    /// it into the enforcer. Returns whether the rule was new.
+    pub async fn save_committed_rule(
+        &self,
+        ptype: &str,
+        rule: Vec<String>,
+    ) -> anyhow::Result<bool> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        if !self.save_casbin_rule(&mut tx, ptype, &rule).await? {
+            return Ok(false);
+        }
+        tx.commit().await.context("failed to commit")?;
+        // The rule is saved either way; see `load_user_policy`.
+        if let Err(e) = self.enforcer.load_committed_policy(ptype, rule).await {
+            tracing::error!("failed to load a {} rule: {:?}", ptype, e);
+        }
+        Ok(true)
+    }
+
+    /// The counterpart of `save_committed_rule`. Returns whether the rule
+    /// existed.
+    pub async fn delete_committed_rule(
+        &self,
+        ptype: &str,
+        rule: Vec<String>,
+    ) -> anyhow::Result<bool> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed t start transaction")?;
+        if !self.delete_casbin_rule(&mut tx, ptype, &rule).await? {
+            return Ok(false);
+        }
+        tx.commit().await.context("failed to commit")?;
+        if let Err(e) = self.enforcer.unload_committed_policy(ptype, rule).await {
+            tracing::error!("failed to unload a {} rule: {:?}", ptype, e);
+        }
+        Ok(true)
+    }
+
+    /// Deletes every `p` and `g` rule whose subject is `subject` inside `tx`.
+    pub async fn delete_casbin_rules_by_subject(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        subject: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM casbin_rule WHERE ptype IN ('p', 'g') AND v0 = $1
+            "#,
+        )
+        .bind(subject.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+}
+
+fn rule_values(rule: &[String]) -> Vec<String> {
+    let mut values = rule.to_vec();
+    values.resize(RULE_COLUMNS, String::new());
+    values
+}
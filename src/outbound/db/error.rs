@@ -0,0 +1,29 @@
+use crate::domain::blog::error::Error;
+
+/// Turns database failures that callers can act on into typed domain errors.
+/// Anything else is kept as an unknown error.
+pub trait DbResultExt<T> {
+    /// Reports a query that expected a row but found none as `NotFound`.
+    fn or_not_found(self, message: &str) -> Result<T, Error>;
+
+    /// Reports a unique constraint violation as `Conflict`.
+    fn or_conflict(self, message: &str) -> Result<T, Error>;
+}
+
+impl<T> DbResultExt<T> for anyhow::Result<T> {
+    fn or_not_found(self, message: &str) -> Result<T, Error> {
+        self.map_err(|err| match err.downcast_ref::<sqlx::Error>() {
+            Some(sqlx::Error::RowNotFound) => Error::NotFound(message.to_string()),
+            _ => Error::Unknown(err),
+        })
+    }
+
+    fn or_conflict(self, message: &str) -> Result<T, Error> {
+        self.map_err(|err| match err.downcast_ref::<sqlx::Error>() {
+            Some(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+                Error::Conflict(message.to_string())
+            }
+            _ => Error::Unknown(err),
+        })
+    }
+}
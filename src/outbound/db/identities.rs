@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::domain::blog::models::{
+    oidc::{ExternalIdentity, OidcLoginState},
+    users::User,
+};
+
+use super::postgres::Pg;
+
+impl Pg {
+    pub async fn get_user_by_identity(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        issuer: &str,
+        subject: &str,
+    ) -> anyhow::Result<Option<User>> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            SELECT u.* FROM users u
+            JOIN user_identities i ON i.user_id = u.id
+            WHERE i.issuer = $1 AND i.subject = $2
+            "#,
+        )
+        .bind(issuer.to_string())
+        .bind(subject.to_string())
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(user)
+    }
+
+    pub async fn save_user_identity(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        identity: &ExternalIdentity,
+    ) -> anyhow::Result<()> {
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO user_identities (id, user_id, issuer, subject, email)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .bind(identity.issuer.clone())
+        .bind(identity.subject.clone())
+        .bind(identity.email.clone())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn save_oidc_login_state(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        state_hash: &str,
+        state: &OidcLoginState,
+        expires_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO oidc_login_states (state_hash, nonce, code_verifier, expires_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(state_hash.to_string())
+        .bind(state.nonce.clone())
+        .bind(state.code_verifier.clone())
+        .bind(expires_at)
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    /// Deletes the state so it can only be used once, returning its nonce
+    /// and code verifier when it has not expired.
+    pub async fn delete_oidc_login_state(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        state_hash: &str,
+    ) -> anyhow::Result<Option<(String, String)>> {
+        let res: Option<(String, String)> = sqlx::query_as(
+            r#"
+            DELETE FROM oidc_login_states WHERE state_hash = $1 AND expires_at > NOW()
+            RETURNING nonce, code_verifier
+            "#,
+        )
+        .bind(state_hash.to_string())
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(res)
+    }
+}
@@ -0,0 +1,62 @@
+use sqlx::{Postgres, Transaction};
+
+use crate::domain::blog::models::auth::LoginAttempt;
+
+use super::postgres::Pg;
+
+impl Pg {
+    /// Locks the row so concurrent logins cannot both slip past the backoff.
+    pub async fn get_login_attempt_for_update(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        key: &str,
+    ) -> anyhow::Result<Option<LoginAttempt>> {
+        let attempt = sqlx::query_as::<_, LoginAttempt>(
+            r#"
+            SELECT * FROM login_attempts WHERE key = $1 FOR UPDATE
+            "#,
+        )
+        .bind(key.to_string())
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(attempt)
+    }
+
+    pub async fn save_login_attempt(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        attempt: &LoginAttempt,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO login_attempts (key, failures, last_failed_at, locked_until)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (key) DO UPDATE
+            SET failures = $2, last_failed_at = $3, locked_until = $4
+            "#,
+        )
+        .bind(attempt.key.clone())
+        .bind(attempt.failures)
+        .bind(attempt.last_failed_at)
+        .bind(attempt.locked_until)
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn clear_login_attempt(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        key: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM login_attempts WHERE key = $1
+            "#,
+        )
+        .bind(key.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+}
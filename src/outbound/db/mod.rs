@@ -1,4 +1,18 @@
+pub mod access_tokens;
 pub mod blog;
+pub mod casbin_rules;
+pub mod email_verifications;
+pub mod error;
+pub mod identities;
+pub mod login_attempts;
+pub mod password_resets;
 pub mod postgres;
 pub mod posts;
+pub mod refresh_tokens;
+pub mod revisions;
+pub mod revocation_cache;
+pub mod revoked_tokens;
+pub mod search;
+pub mod tags;
+pub mod two_factor;
 pub mod users;
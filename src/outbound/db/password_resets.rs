@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use super::postgres::Pg;
+
+impl Pg {
+    /// Stores a new reset token for the user, replacing any unused one.
+    pub async fn save_password_reset_token(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM password_reset_tokens WHERE user_id = $1 AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO password_reset_tokens (id, user_id, token_hash, expires_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .bind(token_hash.to_string())
+        .bind(expires_at)
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    /// Marks a valid reset token as used and returns the user it belongs to.
+    /// Unknown, expired and already used tokens all yield `None`.
+    pub async fn use_password_reset_token(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let res: Option<(String,)> = sqlx::query_as(
+            r#"
+            UPDATE password_reset_tokens SET used_at = NOW()
+            WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+            RETURNING user_id
+            "#,
+        )
+        .bind(token_hash.to_string())
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(res.map(|r| r.0))
+    }
+}
@@ -5,7 +5,7 @@ use sqlx::{
     Pool, Postgres,
 };
 use sqlx_adapter::{
-    casbin::{self, CoreApi, DefaultModel, Enforcer, MgmtApi},
+    casbin::{self, function_map, CoreApi, DefaultModel, Enforcer, MgmtApi, RbacApi},
     SqlxAdapter,
 };
 use tokio::sync::RwLock;
@@ -13,6 +13,8 @@ use tracing::info;
 
 use crate::config::DatabaseSettings;
 
+use super::revocation_cache::RevocationCache;
+
 const ACL_MODEL: &str = r#"
 [request_definition]
 r = sub, obj, act
@@ -20,17 +22,21 @@ r = sub, obj, act
 [policy_definition]
 p = sub, obj, act
 
+[role_definition]
+g = _, _
+
 [policy_effect]
 e = some(where (p.eft == allow))
 
 [matchers]
-m = r.sub == p.sub && keyMatch(r.obj, p.obj) && regexMatch(r.act, p.act)
+m = g(r.sub, p.sub) && keyMatch(r.obj, p.obj) && regexMatch(r.act, p.act)
 "#;
 
 #[derive(Debug, Clone)]
 pub struct Pg {
     pub pool: Pool<Postgres>,
     pub enforcer: EnforcerWrapper,
+    pub revocations: RevocationCache,
 }
 
 #[derive(Clone)]
@@ -41,17 +47,115 @@ impl EnforcerWrapper {
         Self(Arc::new(RwLock::new(enforcer)))
     }
 
-    pub async fn add_policy(&self, ptype: &str, params: Vec<String>) -> anyhow::Result<()> {
-        self.0.write().await.add_named_policy(ptype, params).await?;
-        Ok(())
+    /// Returns whether the rule was new.
+    pub async fn add_policy(&self, ptype: &str, params: Vec<String>) -> anyhow::Result<bool> {
+        let added = self.0.write().await.add_named_policy(ptype, params).await?;
+        Ok(added)
     }
 
-    pub async fn remove_policy(&self, ptype: &str, params: Vec<String>) -> anyhow::Result<()> {
-        self.0
+    /// Returns whether the rule existed.
+    pub async fn remove_policy(&self, ptype: &str, params: Vec<String>) -> anyhow::Result<bool> {
+        let removed = self
+            .0
             .write()
             .await
             .remove_named_policy(ptype, params)
             .await?;
+        Ok(removed)
+    }
+
+    pub async fn policies(&self, ptype: &str) -> Vec<Vec<String>> {
+        self.0.read().await.get_named_policy(ptype)
+    }
+
+    pub async fn grouping_policies(&self, ptype: &str) -> Vec<Vec<String>> {
+        self.0.read().await.get_named_grouping_policy(ptype)
+    }
+
+    /// Roles assigned to `name` directly, and all roles it inherits.
+    pub async fn roles_for_user(&self, name: &str) -> (Vec<String>, Vec<String>) {
+        let mut enforcer = self.0.write().await;
+        let roles = enforcer.get_roles_for_user(name, None);
+        let implicit_roles = enforcer.get_implicit_roles_for_user(name, None);
+        (roles, implicit_roles)
+    }
+
+    /// Enforces the request and finds the first `p` rule that grants it.
+    /// The search mirrors the matcher in `ACL_MODEL` and must change with it.
+    pub async fn explain(
+        &self,
+        sub: &str,
+        obj: &str,
+        act: &str,
+    ) -> anyhow::Result<(bool, Option<Vec<String>>)> {
+        let enforcer = self.0.read().await;
+        let allowed = enforcer.enforce(vec![sub.to_string(), obj.to_string(), act.to_string()])?;
+        let rm = enforcer.get_role_manager();
+        let rm = rm.read();
+        let rule = enforcer.get_named_policy("p").into_iter().find(|rule| {
+            matches!(rule.as_slice(), [p_sub, p_obj, p_act, ..]
+                if rm.has_link(sub, p_sub, None)
+                    && function_map::key_match(obj, p_obj)
+                    && function_map::regex_match(act, p_act))
+        });
+        Ok((allowed, rule))
+    }
+
+    /// Adds a rule that a transaction of ours already committed to
+    /// `casbin_rule` to the in-memory model, without writing it again
+    /// through the adapter.
+    pub async fn load_committed_policy(
+        &self,
+        ptype: &str,
+        params: Vec<String>,
+    ) -> anyhow::Result<()> {
+        let mut enforcer = self.0.write().await;
+        enforcer.enable_auto_save(false);
+        let res = if ptype.starts_with('g') {
+            enforcer.add_named_grouping_policy(ptype, params).await
+        } else {
+            enforcer.add_named_policy(ptype, params).await
+        };
+        enforcer.enable_auto_save(true);
+        res?;
+        Ok(())
+    }
+
+    /// Removes a rule that a transaction of ours already deleted from
+    /// `casbin_rule` from the in-memory model, like `load_committed_policy`.
+    pub async fn unload_committed_policy(
+        &self,
+        ptype: &str,
+        params: Vec<String>,
+    ) -> anyhow::Result<()> {
+        let mut enforcer = self.0.write().await;
+        enforcer.enable_auto_save(false);
+        let res = if ptype.starts_with('g') {
+            enforcer.remove_named_grouping_policy(ptype, params).await
+        } else {
+            enforcer.remove_named_policy(ptype, params).await
+        };
+        enforcer.enable_auto_save(true);
+        res?;
+        Ok(())
+    }
+
+    /// Removes the `p` and `g` rules of `subject` from the in-memory model
+    /// after a transaction of ours deleted them from `casbin_rule`.
+    pub async fn unload_committed_subject(&self, subject: &str) -> anyhow::Result<()> {
+        let mut enforcer = self.0.write().await;
+        enforcer.enable_auto_save(false);
+        let res = async {
+            enforcer
+                .remove_filtered_named_policy("p", 0, vec![subject.to_string()])
+                .await?;
+            enforcer
+                .remove_filtered_named_grouping_policy("g", 0, vec![subject.to_string()])
+                .await
+        }
+        .await;
+        enforcer.enable_auto_save(true);
+        res?;
         Ok(())
     }
 
@@ -80,12 +184,19 @@ impl Pg {
             .password(&config.password)
             .database(&config.database_name);
         let pool = PgPoolOptions::new().connect_with(opts).await?;
+        Self::from_pool(pool).await
+    }
+
+    /// Builds the repository on an existing pool whose database is already
+    /// migrated.
+    pub async fn from_pool(pool: Pool<Postgres>) -> anyhow::Result<Self> {
         let model = DefaultModel::from_str(ACL_MODEL).await?;
         let adapter = SqlxAdapter::new_with_pool(pool.clone()).await?;
         let enforcer = casbin::Enforcer::new(model, adapter).await?;
         Ok(Self {
             pool,
             enforcer: EnforcerWrapper::new(enforcer),
+            revocations: RevocationCache::default(),
         })
     }
 }
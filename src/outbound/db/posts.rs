@@ -1,7 +1,13 @@
+use chrono::{DateTime, Utc};
 use sqlx::{Postgres, Transaction};
 use uuid::Uuid;
 
-use crate::domain::blog::models::posts::Post;
+use crate::{
+    domain::blog::models::posts::{
+        CreatePostRequest, ListPostRequest, Post, PostStatus, TagMatch, UpdatePostRequest,
+    },
+    utils::slugify,
+};
 
 use super::postgres::Pg;
 
@@ -9,48 +15,274 @@ impl Pg {
     pub async fn save_post(
         &self,
         tx: &mut Transaction<'_, Postgres>,
-        title: &str,
-        content: &str,
-        username: &str,
+        req: &CreatePostRequest,
+        slug: &str,
     ) -> anyhow::Result<Post> {
         let id = Uuid::new_v4();
         let post = sqlx::query_as::<_, Post>(
             r#"
-            INSERT INTO posts (id, title, content, username) VALUES ($1, $2, $3, $4)
+            INSERT INTO posts (id, title, slug, content, content_html, username, publish_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             RETURNING *
             "#,
         )
         .bind(id.to_string())
-        .bind(title.to_string())
-        .bind(content.to_string())
-        .bind(username.to_string())
+        .bind(req.title.to_string())
+        .bind(slug.to_string())
+        .bind(req.content.to_string())
+        .bind(req.content_html.to_string())
+        .bind(req.username.to_string())
+        .bind(req.publish_at)
         .fetch_one(tx.as_mut())
         .await?;
         Ok(post)
     }
 
-    pub async fn get_post(
+    /// Username of the post's author, for trashed posts too.
+    pub async fn get_post_author(
         &self,
         tx: &mut Transaction<'_, Postgres>,
-        id: &Uuid,
-    ) -> anyhow::Result<Post> {
+        id: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let res: Option<(String,)> = sqlx::query_as(
+            r#"
+            SELECT username FROM posts WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(res.map(|(username,)| username))
+    }
+
+    pub async fn get_post_by_id_and_username_for_update(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        username: &str,
+    ) -> anyhow::Result<Option<Post>> {
         let post = sqlx::query_as::<_, Post>(
             r#"
-            SELECT id, title, content, created_at, updated_at FROM posts WHERE id = $1
+            SELECT id, title, slug, content, content_html, status, published_at, publish_at, deleted_at, created_at, updated_at FROM posts
+            WHERE id = $1 AND username = $2 AND deleted_at IS NULL
+            FOR UPDATE
             "#,
         )
         .bind(id.to_string())
-        .fetch_one(tx.as_mut())
+        .bind(username.to_string())
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(post)
+    }
+
+    pub async fn get_post_by_id_and_username(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        username: &str,
+    ) -> anyhow::Result<Option<Post>> {
+        let post = sqlx::query_as::<_, Post>(
+            r#"
+            SELECT id, title, slug, content, content_html, status, published_at, publish_at, deleted_at, created_at, updated_at FROM posts
+            WHERE id = $1 AND username = $2 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(username.to_string())
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(post)
+    }
+
+    pub async fn get_published_post(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+    ) -> anyhow::Result<Option<Post>> {
+        let post = sqlx::query_as::<_, Post>(
+            r#"
+            SELECT id, title, slug, content, content_html, status, published_at, publish_at, deleted_at, created_at, updated_at FROM posts
+            WHERE id = $1 AND status = 'published' AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(tx.as_mut())
         .await?;
         Ok(post)
     }
 
-    pub async fn post_count(&self, tx: &mut Transaction<'_, Postgres>) -> anyhow::Result<u64> {
+    pub async fn get_post_by_slug(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        username: &str,
+        slug: &str,
+        status: Option<PostStatus>,
+    ) -> anyhow::Result<Option<Post>> {
+        let post = sqlx::query_as::<_, Post>(
+            r#"
+            SELECT id, title, slug, content, content_html, status, published_at, publish_at, deleted_at, created_at, updated_at FROM posts
+            WHERE username = $1 AND slug = $2 AND deleted_at IS NULL
+                AND ($3::post_status IS NULL OR status = $3)
+            "#,
+        )
+        .bind(username.to_string())
+        .bind(slug.to_string())
+        .bind(status)
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(post)
+    }
+
+    /// Looks up an old slug in the history and returns the current slug of
+    /// the post it used to point to.
+    pub async fn get_current_slug_by_old_slug(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        username: &str,
+        slug: &str,
+        status: Option<PostStatus>,
+    ) -> anyhow::Result<Option<String>> {
+        let res: Option<(String,)> = sqlx::query_as(
+            r#"
+            SELECT p.slug FROM post_slug_history h
+            JOIN posts p ON p.id = h.post_id
+            WHERE h.username = $1 AND h.slug = $2 AND p.deleted_at IS NULL
+                AND ($3::post_status IS NULL OR p.status = $3)
+            "#,
+        )
+        .bind(username.to_string())
+        .bind(slug.to_string())
+        .bind(status)
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(res.map(|r| r.0))
+    }
+
+    /// Returns `slug` if the author has no other post currently or previously
+    /// using it, otherwise the first free `slug-N` suffix.
+    pub async fn unique_slug(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        username: &str,
+        slug: &str,
+        post_id: Option<&str>,
+    ) -> anyhow::Result<String> {
+        let mut candidate = slug.to_string();
+        let mut suffix = 1;
+        loop {
+            let (taken,): (bool,) = sqlx::query_as(
+                r#"
+                SELECT
+                    EXISTS (
+                        SELECT 1 FROM posts
+                        WHERE username = $1 AND slug = $2 AND id IS DISTINCT FROM $3
+                    )
+                    OR EXISTS (
+                        SELECT 1 FROM post_slug_history
+                        WHERE username = $1 AND slug = $2 AND post_id IS DISTINCT FROM $3
+                    )
+                "#,
+            )
+            .bind(username.to_string())
+            .bind(&candidate)
+            .bind(post_id)
+            .fetch_one(tx.as_mut())
+            .await?;
+            if !taken {
+                return Ok(candidate);
+            }
+            suffix += 1;
+            candidate = format!("{slug}-{suffix}");
+        }
+    }
+
+    /// Returns the slug `current` should carry once retitled to `slug`. When
+    /// it changes, the old slug is kept in the history so it still resolves.
+    pub async fn rename_slug(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        current: &Post,
+        username: &str,
+        slug: &str,
+    ) -> anyhow::Result<String> {
+        if slugify(&current.title) == slug {
+            return Ok(current.slug.clone());
+        }
+        let slug = self
+            .unique_slug(tx, username, slug, Some(&current.id))
+            .await?;
+        if slug != current.slug {
+            self.save_slug_history(tx, username, &current.slug, &current.id)
+                .await?;
+            self.delete_slug_history(tx, username, &slug).await?;
+        }
+        Ok(slug)
+    }
+
+    pub async fn save_slug_history(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        username: &str,
+        slug: &str,
+        post_id: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO post_slug_history (username, slug, post_id) VALUES ($1, $2, $3)
+            ON CONFLICT (username, slug) DO UPDATE SET post_id = EXCLUDED.post_id
+            "#,
+        )
+        .bind(username.to_string())
+        .bind(slug.to_string())
+        .bind(post_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_slug_history(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        username: &str,
+        slug: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM post_slug_history WHERE username = $1 AND slug = $2
+            "#,
+        )
+        .bind(username.to_string())
+        .bind(slug.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn post_count(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        req: &ListPostRequest,
+    ) -> anyhow::Result<u64> {
         let count: (i64,) = sqlx::query_as(
             r#"
             SELECT COUNT(id) FROM posts
+            WHERE
+                username = $1
+                AND deleted_at IS NULL
+                AND ($2::post_status IS NULL OR status = $2)
+                AND (
+                    cardinality($3::text[]) = 0
+                    OR (
+                        SELECT COUNT(*) FROM post_tags pt JOIN tags t ON t.id = pt.tag_id
+                        WHERE pt.post_id = posts.id AND t.name = ANY($3)
+                    ) >= CASE WHEN $4 THEN cardinality($3::text[]) ELSE 1 END
+                )
             "#,
         )
+        .bind(req.username.to_string())
+        .bind(req.status)
+        .bind(&req.tags)
+        .bind(req.tag_match == TagMatch::All)
         .fetch_one(tx.as_mut())
         .await?;
         Ok(count.0 as u64)
@@ -59,85 +291,278 @@ impl Pg {
     pub async fn list_post(
         &self,
         tx: &mut Transaction<'_, Postgres>,
-        offset: u32,
-        limit: u32,
-        username: &str,
+        req: &ListPostRequest,
     ) -> anyhow::Result<Vec<Post>> {
         let res = sqlx::query_as::<_, Post>(
             r#"
             SELECT
-                id, title, content, created_at, updated_at
+                id, title, slug, content, content_html, status, published_at, publish_at, deleted_at, created_at, updated_at
             FROM
                 posts
             where
                 username = $1
+                AND deleted_at IS NULL
+                AND ($4::post_status IS NULL OR status = $4)
+                AND (
+                    cardinality($5::text[]) = 0
+                    OR (
+                        SELECT COUNT(*) FROM post_tags pt JOIN tags t ON t.id = pt.tag_id
+                        WHERE pt.post_id = posts.id AND t.name = ANY($5)
+                    ) >= CASE WHEN $6 THEN cardinality($5::text[]) ELSE 1 END
+                )
             ORDER BY created_at DESC OFFSET $2 LIMIT $3
             "#,
         )
-        .bind(username.to_string())
-        .bind(offset as i64)
-        .bind(limit as i64)
+        .bind(req.username.to_string())
+        .bind(req.offset as i64)
+        .bind(req.limit as i64)
+        .bind(req.status)
+        .bind(&req.tags)
+        .bind(req.tag_match == TagMatch::All)
         .fetch_all(tx.as_mut())
         .await?;
         Ok(res)
     }
 
     pub async fn update_post(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        req: &UpdatePostRequest,
+        username: &str,
+        slug: &str,
+    ) -> anyhow::Result<Post> {
+        let post = sqlx::query_as::<_, Post>(
+            r#"
+            UPDATE posts
+            SET title = $1, slug = $2, content = $3, content_html = $7, publish_at = $4, updated_at = NOW()
+            WHERE id = $5 AND username = $6
+            RETURNING *
+            "#,
+        )
+        .bind(req.title.to_string())
+        .bind(slug.to_string())
+        .bind(req.content.to_string())
+        .bind(req.publish_at)
+        .bind(req.id.to_string())
+        .bind(username.to_string())
+        .bind(req.content_html.to_string())
+        .fetch_one(tx.as_mut())
+        .await?;
+        Ok(post)
+    }
+
+    pub async fn update_post_content(
         &self,
         tx: &mut Transaction<'_, Postgres>,
         id: &str,
         title: &str,
+        slug: &str,
         content: &str,
-        username: &str,
+        content_html: &str,
     ) -> anyhow::Result<Post> {
         let post = sqlx::query_as::<_, Post>(
             r#"
-            UPDATE posts SET title = $1, content = $2, updated_at = NOW() WHERE id = $3 AND username = $4
+            UPDATE posts SET title = $1, slug = $2, content = $3, content_html = $5, updated_at = NOW()
+            WHERE id = $4
             RETURNING *
             "#,
         )
         .bind(title.to_string())
+        .bind(slug.to_string())
         .bind(content.to_string())
         .bind(id.to_string())
-        .bind(username.to_string())
+        .bind(content_html.to_string())
+        .fetch_one(tx.as_mut())
+        .await?;
+        Ok(post)
+    }
+
+    pub async fn update_post_status(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        status: PostStatus,
+    ) -> anyhow::Result<Post> {
+        let post = sqlx::query_as::<_, Post>(
+            r#"
+            UPDATE posts
+            SET
+                status = $1,
+                published_at = CASE WHEN $1 = 'published' THEN NOW() ELSE published_at END,
+                publish_at = NULL,
+                updated_at = NOW()
+            WHERE id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(status)
+        .bind(id.to_string())
         .fetch_one(tx.as_mut())
         .await?;
         Ok(post)
     }
 
-    pub async fn delete_post_by_id_and_username(
+    /// Publishes up to `limit` due drafts. Rows are claimed with
+    /// `FOR UPDATE SKIP LOCKED`, so concurrent schedulers running on other
+    /// instances never pick up the same post.
+    pub async fn publish_due_posts(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Post>> {
+        let posts = sqlx::query_as::<_, Post>(
+            r#"
+            UPDATE posts
+            SET status = 'published', published_at = NOW(), publish_at = NULL, updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM posts
+                WHERE status = 'draft' AND publish_at <= NOW() AND deleted_at IS NULL
+                ORDER BY publish_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(limit as i64)
+        .fetch_all(tx.as_mut())
+        .await?;
+        Ok(posts)
+    }
+
+    pub async fn trash_post_by_id_and_username(
         &self,
         tx: &mut Transaction<'_, Postgres>,
         id: &str,
         username: &str,
-    ) -> anyhow::Result<()> {
-        sqlx::query(
+    ) -> anyhow::Result<bool> {
+        let res = sqlx::query(
             r#"
-            DELETE FROM posts WHERE id = $1 AND username = $2
+            UPDATE posts SET deleted_at = NOW() WHERE id = $1 AND username = $2 AND deleted_at IS NULL
             "#,
         )
         .bind(id.to_string())
         .bind(username.to_string())
         .execute(tx.as_mut())
         .await?;
-        Ok(())
+        Ok(res.rows_affected() > 0)
     }
 
-    pub async fn delete_posts_by_ids(
+    /// Trashes the posts among `ids` written by `username`, or by anyone
+    /// when it is `None`.
+    pub async fn trash_posts_by_ids(
         &self,
         tx: &mut Transaction<'_, Postgres>,
         ids: Vec<String>,
-        username: &str,
+        username: Option<&str>,
     ) -> anyhow::Result<()> {
         sqlx::query(
             r#"
-            DELETE FROM posts WHERE id = ANY($1) AND username = $2
+            UPDATE posts SET deleted_at = NOW()
+            WHERE id = ANY($1) AND ($2::TEXT IS NULL OR username = $2) AND deleted_at IS NULL
             "#,
         )
         .bind(ids)
-        .bind(username.to_string())
+        .bind(username.map(str::to_string))
         .execute(tx.as_mut())
         .await?;
         Ok(())
     }
+
+    pub async fn trashed_post_count(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        username: &str,
+    ) -> anyhow::Result<u64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(id) FROM posts WHERE username = $1 AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(username.to_string())
+        .fetch_one(tx.as_mut())
+        .await?;
+        Ok(count.0 as u64)
+    }
+
+    pub async fn list_trashed_posts(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        offset: u32,
+        limit: u32,
+        username: &str,
+    ) -> anyhow::Result<Vec<Post>> {
+        let res = sqlx::query_as::<_, Post>(
+            r#"
+            SELECT
+                id, title, slug, content, content_html, status, published_at, publish_at, deleted_at, created_at, updated_at
+            FROM
+                posts
+            where
+                username = $1 AND deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC OFFSET $2 LIMIT $3
+            "#,
+        )
+        .bind(username.to_string())
+        .bind(offset as i64)
+        .bind(limit as i64)
+        .fetch_all(tx.as_mut())
+        .await?;
+        Ok(res)
+    }
+
+    pub async fn restore_trashed_post(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        username: &str,
+    ) -> anyhow::Result<Option<Post>> {
+        let post = sqlx::query_as::<_, Post>(
+            r#"
+            UPDATE posts SET deleted_at = NULL, updated_at = NOW()
+            WHERE id = $1 AND username = $2 AND deleted_at IS NOT NULL
+            RETURNING *
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(username.to_string())
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(post)
+    }
+
+    /// Permanently deletes a post from the trash, returning whether it was there.
+    pub async fn delete_trashed_post(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        username: &str,
+    ) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"
+            DELETE FROM posts WHERE id = $1 AND username = $2 AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(username.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    pub async fn purge_trashed_posts(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        deleted_before: DateTime<Utc>,
+    ) -> anyhow::Result<u64> {
+        let res = sqlx::query(
+            r#"
+            DELETE FROM posts WHERE deleted_at < $1
+            "#,
+        )
+        .bind(deleted_before)
+        .execute(tx.as_mut())
+        .await?;
+        Ok(res.rows_affected())
+    }
 }
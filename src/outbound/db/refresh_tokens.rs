@@ -0,0 +1,120 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::auth::{RefreshToken, TokenPair},
+    },
+    utils::{jwt::JWT, token},
+};
+
+use super::postgres::Pg;
+
+impl Pg {
+    pub async fn save_refresh_token(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        family_id: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> anyhow::Result<RefreshToken> {
+        let id = Uuid::new_v4();
+        let token = sqlx::query_as::<_, RefreshToken>(
+            r#"
+            INSERT INTO refresh_tokens (id, user_id, family_id, token_hash, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .bind(family_id.to_string())
+        .bind(token_hash.to_string())
+        .bind(expires_at)
+        .fetch_one(tx.as_mut())
+        .await?;
+        Ok(token)
+    }
+
+    pub async fn get_refresh_token_by_hash_for_update(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<RefreshToken>> {
+        let token = sqlx::query_as::<_, RefreshToken>(
+            r#"
+            SELECT * FROM refresh_tokens WHERE token_hash = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(token_hash.to_string())
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(token)
+    }
+
+    pub async fn mark_refresh_token_used(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE refresh_tokens SET used_at = NOW() WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn revoke_refresh_token_family(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        family_id: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE refresh_tokens SET revoked_at = NOW()
+            WHERE family_id = $1 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(family_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    /// Signs a new access token for `user_id` and stores a fresh refresh
+    /// token in `family_id`.
+    pub async fn issue_token_pair(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        family_id: &str,
+        jwt: &JWT,
+        expiration: u64,
+        refresh_expiration: u64,
+    ) -> Result<TokenPair, Error> {
+        let access_token = jwt.generate_token(&expiration, user_id.to_string(), None)?;
+        let refresh_token = token::generate_token();
+        let expires_at = Utc::now() + Duration::seconds(refresh_expiration as i64);
+        self.save_refresh_token(
+            tx,
+            user_id,
+            family_id,
+            &token::hash_token(&refresh_token),
+            expires_at,
+        )
+        .await?;
+        Ok(TokenPair {
+            access_token,
+            refresh_token,
+            expires_in: expiration,
+        })
+    }
+}
@@ -0,0 +1,65 @@
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::domain::blog::models::{posts::Post, revisions::PostRevision};
+
+use super::postgres::Pg;
+
+impl Pg {
+    /// Stores the post's current title and content as its next revision.
+    pub async fn save_post_revision(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        post: &Post,
+    ) -> anyhow::Result<PostRevision> {
+        let id = Uuid::new_v4();
+        let revision = sqlx::query_as::<_, PostRevision>(
+            r#"
+            INSERT INTO post_revisions (id, post_id, revision, title, content)
+            SELECT $1, $2, COALESCE(MAX(revision), 0) + 1, $3, $4 FROM post_revisions WHERE post_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(post.id.to_string())
+        .bind(post.title.to_string())
+        .bind(post.content.to_string())
+        .fetch_one(tx.as_mut())
+        .await?;
+        Ok(revision)
+    }
+
+    pub async fn list_post_revisions(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        post_id: &str,
+    ) -> anyhow::Result<Vec<PostRevision>> {
+        let revisions = sqlx::query_as::<_, PostRevision>(
+            r#"
+            SELECT * FROM post_revisions WHERE post_id = $1 ORDER BY revision DESC
+            "#,
+        )
+        .bind(post_id.to_string())
+        .fetch_all(tx.as_mut())
+        .await?;
+        Ok(revisions)
+    }
+
+    pub async fn get_post_revision(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        post_id: &str,
+        revision: i32,
+    ) -> anyhow::Result<Option<PostRevision>> {
+        let revision = sqlx::query_as::<_, PostRevision>(
+            r#"
+            SELECT * FROM post_revisions WHERE post_id = $1 AND revision = $2
+            "#,
+        )
+        .bind(post_id.to_string())
+        .bind(revision)
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(revision)
+    }
+}
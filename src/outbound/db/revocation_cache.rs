@@ -0,0 +1,44 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+const CAPACITY: usize = 10_000;
+
+/// Bounded cache of revoked token ids in front of `revoked_tokens`. Only
+/// revocations are cached: a revocation is permanent, while a "not revoked"
+/// answer could be overturned by another instance at any time, so those are
+/// always read from the database.
+#[derive(Debug, Clone, Default)]
+pub struct RevocationCache(Arc<Mutex<CacheInner>>);
+
+#[derive(Debug, Default)]
+struct CacheInner {
+    revoked: HashSet<String>,
+    /// Insertion order, oldest first, for evicting once full.
+    order: VecDeque<String>,
+}
+
+impl RevocationCache {
+    pub fn is_revoked(&self, jti: &str) -> bool {
+        let inner = self.0.lock().expect("revocation cache poisoned");
+        inner.revoked.contains(jti)
+    }
+
+    pub fn insert(&self, jti: &str) {
+        let mut inner = self.0.lock().expect("revocation cache poisoned");
+        if inner.revoked.contains(jti) {
+            return;
+        }
+        while inner.revoked.len() >= CAPACITY {
+            match inner.order.pop_front() {
+                Some(oldest) => {
+                    inner.revoked.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+        inner.revoked.insert(jti.to_string());
+        inner.order.push_back(jti.to_string());
+    }
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, Transaction};
+
+use super::postgres::Pg;
+
+impl Pg {
+    pub async fn revoke_token(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        jti: &str,
+        user_id: &str,
+        expires_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO revoked_tokens (jti, user_id, expires_at) VALUES ($1, $2, $3)
+            ON CONFLICT (jti) DO NOTHING
+            "#,
+        )
+        .bind(jti.to_string())
+        .bind(user_id.to_string())
+        .bind(expires_at)
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn token_revoked(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        jti: &str,
+    ) -> anyhow::Result<bool> {
+        let res: (bool,) = sqlx::query_as(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM revoked_tokens WHERE jti = $1)
+            "#,
+        )
+        .bind(jti.to_string())
+        .fetch_one(tx.as_mut())
+        .await?;
+        Ok(res.0)
+    }
+
+    /// Ends every session of the user: access and refresh tokens, and the
+    /// personal access tokens, which would otherwise outlive a password
+    /// change.
+    pub async fn revoke_user_tokens(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE users SET tokens_valid_after = date_trunc('second', NOW()) WHERE id = $1
+            "#,
+        )
+        .bind(user_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        sqlx::query(
+            r#"
+            UPDATE refresh_tokens SET revoked_at = NOW()
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(user_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        self.delete_access_tokens_by_user_id(tx, user_id).await?;
+        Ok(())
+    }
+
+    pub async fn purge_expired_tokens(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+    ) -> anyhow::Result<u64> {
+        let revoked = sqlx::query(
+            r#"
+            DELETE FROM revoked_tokens WHERE expires_at < NOW()
+            "#,
+        )
+        .execute(tx.as_mut())
+        .await?;
+        let refresh = sqlx::query(
+            r#"
+            DELETE FROM refresh_tokens WHERE expires_at < NOW()
+            "#,
+        )
+        .execute(tx.as_mut())
+        .await?;
+        let challenges = sqlx::query(
+            r#"
+            DELETE FROM login_challenges WHERE expires_at < NOW()
+            "#,
+        )
+        .execute(tx.as_mut())
+        .await?;
+        let oidc_states = sqlx::query(
+            r#"
+            DELETE FROM oidc_login_states WHERE expires_at < NOW()
+            "#,
+        )
+        .execute(tx.as_mut())
+        .await?;
+        Ok(revoked.rows_affected()
+            + refresh.rows_affected()
+            + challenges.rows_affected()
+            + oidc_states.rows_affected())
+    }
+}
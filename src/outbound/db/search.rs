@@ -0,0 +1,97 @@
+use sqlx::{Postgres, Transaction};
+
+use crate::domain::blog::models::search::{SearchPostRequest, SearchPostResult};
+
+use super::postgres::Pg;
+
+/// Placeholders `ts_headline` wraps matches in. They are stripped from the
+/// text first, so after escaping the highlight they only ever stand for the
+/// `<mark>` tags.
+const START_SEL: char = '\u{2}';
+const STOP_SEL: char = '\u{3}';
+
+impl Pg {
+    /// Searches the caller's own posts and everybody's published posts,
+    /// best matches first. Highlights are HTML: the text is escaped before
+    /// the matches are wrapped in `<mark>` tags, so post content cannot inject
+    /// markup.
+    pub async fn search_post(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        req: &SearchPostRequest,
+    ) -> anyhow::Result<Vec<SearchPostResult>> {
+        let mut res = sqlx::query_as::<_, SearchPostResult>(
+            r#"
+            SELECT
+                id, title, slug, content, content_html, status, published_at, publish_at, deleted_at, created_at, updated_at,
+                ts_rank_cd(search_vector, query) AS rank,
+                ts_headline('simple', translate(title, $5, ''), query, $6) AS title_highlight,
+                ts_headline('simple', translate(content, $5, ''), query, $7) AS snippet
+            FROM posts, to_tsquery('simple', $1) query
+            WHERE
+                search_vector @@ query
+                AND deleted_at IS NULL
+                AND (username = $2 OR status = 'published')
+            ORDER BY rank DESC, created_at DESC OFFSET $3 LIMIT $4
+            "#,
+        )
+        .bind(req.tsquery.to_string())
+        .bind(req.username.to_string())
+        .bind(req.offset as i64)
+        .bind(req.limit as i64)
+        .bind(format!("{START_SEL}{STOP_SEL}"))
+        .bind(format!(
+            r#"StartSel="{START_SEL}", StopSel="{STOP_SEL}", HighlightAll=true"#
+        ))
+        .bind(format!(
+            r#"StartSel="{START_SEL}", StopSel="{STOP_SEL}", MaxWords=35, MinWords=15, MaxFragments=2"#
+        ))
+        .fetch_all(tx.as_mut())
+        .await?;
+        for result in &mut res {
+            result.title_highlight = highlight_html(&result.title_highlight);
+            result.snippet = highlight_html(&result.snippet);
+        }
+        Ok(res)
+    }
+
+    pub async fn search_post_count(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        req: &SearchPostRequest,
+    ) -> anyhow::Result<u64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(id) FROM posts
+            WHERE
+                search_vector @@ to_tsquery('simple', $1)
+                AND deleted_at IS NULL
+                AND (username = $2 OR status = 'published')
+            "#,
+        )
+        .bind(req.tsquery.to_string())
+        .bind(req.username.to_string())
+        .fetch_one(tx.as_mut())
+        .await?;
+        Ok(count.0 as u64)
+    }
+}
+
+/// Escapes a `ts_headline` result and turns its placeholders into `<mark>`
+/// tags.
+fn highlight_html(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            START_SEL => html.push_str("<mark>"),
+            STOP_SEL => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            _ => html.push(c),
+        }
+    }
+    html
+}
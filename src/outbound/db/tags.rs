@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::domain::blog::models::{posts::Post, tags::Tag};
+
+use super::postgres::Pg;
+
+impl Pg {
+    /// Replaces the tags of a post, creating any tag that does not exist yet.
+    pub async fn set_post_tags(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        post_id: &str,
+        tags: &[String],
+    ) -> anyhow::Result<()> {
+        let ids: Vec<String> = tags.iter().map(|_| Uuid::new_v4().to_string()).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO tags (id, name) SELECT * FROM UNNEST($1::text[], $2::text[])
+            ON CONFLICT (name) DO NOTHING
+            "#,
+        )
+        .bind(ids)
+        .bind(tags)
+        .execute(tx.as_mut())
+        .await?;
+        sqlx::query(
+            r#"
+            DELETE FROM post_tags WHERE post_id = $1
+            "#,
+        )
+        .bind(post_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO post_tags (post_id, tag_id) SELECT $1, id FROM tags WHERE name = ANY($2)
+            "#,
+        )
+        .bind(post_id.to_string())
+        .bind(tags)
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    /// Loads the tags of every given post with a single query.
+    pub async fn attach_tags<'a>(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        posts: impl IntoIterator<Item = &'a mut Post>,
+    ) -> anyhow::Result<()> {
+        let posts: Vec<&mut Post> = posts.into_iter().collect();
+        if posts.is_empty() {
+            return Ok(());
+        }
+        let ids: Vec<String> = posts.iter().map(|p| p.id.clone()).collect();
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT pt.post_id, t.name FROM post_tags pt
+            JOIN tags t ON t.id = pt.tag_id
+            WHERE pt.post_id = ANY($1)
+            ORDER BY t.name
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(tx.as_mut())
+        .await?;
+        let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+        for (post_id, name) in rows {
+            tags.entry(post_id).or_default().push(name);
+        }
+        for post in posts {
+            post.tags = tags.remove(&post.id).unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    /// Lists the tags of published posts with the number of published posts
+    /// carrying each. Tags used only on drafts stay private to their authors.
+    pub async fn list_tags(&self, tx: &mut Transaction<'_, Postgres>) -> anyhow::Result<Vec<Tag>> {
+        let tags = sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT t.name, COUNT(p.id) AS post_count FROM tags t
+            JOIN post_tags pt ON pt.tag_id = t.id
+            JOIN posts p
+                ON p.id = pt.post_id AND p.status = 'published' AND p.deleted_at IS NULL
+            GROUP BY t.name
+            ORDER BY post_count DESC, t.name
+            "#,
+        )
+        .fetch_all(tx.as_mut())
+        .await?;
+        Ok(tags)
+    }
+}
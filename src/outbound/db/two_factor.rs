@@ -0,0 +1,321 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    domain::blog::{
+        error::Error,
+        models::{
+            auth::{LoginAttempt, LoginResponse, LoginThrottle},
+            two_factor::{LoginChallenge, RecoveryCode},
+            users::User,
+        },
+    },
+    utils::{token, totp, verify_password_hash},
+};
+
+use super::postgres::Pg;
+
+/// Wrong codes a login challenge tolerates before it is spent.
+pub const MAX_CHALLENGE_FAILURES: i32 = 5;
+
+impl Pg {
+    /// Stores a pending secret, replacing any earlier unconfirmed one.
+    pub async fn set_totp_secret(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        secret: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET totp_secret = $1, totp_enabled_at = NULL, totp_last_step = NULL, updated_at = NOW()
+            WHERE id = $2
+            "#,
+        )
+        .bind(secret.to_string())
+        .bind(user_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn enable_totp(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        step: u64,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE users SET totp_enabled_at = NOW(), totp_last_step = $1, updated_at = NOW()
+            WHERE id = $2
+            "#,
+        )
+        .bind(step as i64)
+        .bind(user_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    /// Turns 2FA off and throws away the secret and recovery codes.
+    pub async fn clear_totp(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET totp_secret = NULL, totp_enabled_at = NULL, totp_last_step = NULL, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        sqlx::query(
+            r#"
+            DELETE FROM recovery_codes WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    /// Records `step` as used unless it, or a later one, already was. The
+    /// check happens in the update so concurrent logins cannot both pass it.
+    pub async fn claim_totp_step(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        step: u64,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users SET totp_last_step = $1
+            WHERE id = $2 AND (totp_last_step IS NULL OR totp_last_step < $1)
+            "#,
+        )
+        .bind(step as i64)
+        .bind(user_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn replace_recovery_codes(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        code_hashes: &[String],
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM recovery_codes WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        for code_hash in code_hashes {
+            let id = Uuid::new_v4();
+            sqlx::query(
+                r#"
+                INSERT INTO recovery_codes (id, user_id, code_hash) VALUES ($1, $2, $3)
+                "#,
+            )
+            .bind(id.to_string())
+            .bind(user_id.to_string())
+            .bind(code_hash.clone())
+            .execute(tx.as_mut())
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_unused_recovery_codes(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+    ) -> anyhow::Result<Vec<RecoveryCode>> {
+        let codes = sqlx::query_as::<_, RecoveryCode>(
+            r#"
+            SELECT * FROM recovery_codes WHERE user_id = $1 AND used_at IS NULL FOR UPDATE
+            "#,
+        )
+        .bind(user_id.to_string())
+        .fetch_all(tx.as_mut())
+        .await?;
+        Ok(codes)
+    }
+
+    pub async fn mark_recovery_code_used(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE recovery_codes SET used_at = NOW() WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    /// Accepts a TOTP code that was not used before, or burns a matching
+    /// recovery code.
+    pub async fn verify_second_factor(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user: &User,
+        code: &str,
+    ) -> anyhow::Result<bool> {
+        if let Some(secret) = &user.totp_secret {
+            let now = Utc::now().timestamp() as u64;
+            if let Some(step) = totp::verify(secret, code.trim(), now) {
+                return self.claim_totp_step(tx, &user.id, step).await;
+            }
+        }
+        let code = totp::normalize_recovery_code(code);
+        if code.is_empty() {
+            return Ok(false);
+        }
+        for recovery_code in self.get_unused_recovery_codes(tx, &user.id).await? {
+            if verify_password_hash(recovery_code.code_hash, code.clone()).is_ok() {
+                self.mark_recovery_code_used(tx, &recovery_code.id).await?;
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// `verify_second_factor` behind the login throttle of the username, so
+    /// codes cannot be guessed faster than passwords. Failures are recorded
+    /// in `tx`, which the caller must commit even when the code is wrong.
+    pub async fn verify_throttled_second_factor(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user: &User,
+        code: &str,
+        throttle: &LoginThrottle,
+    ) -> Result<bool, Error> {
+        let now = Utc::now();
+        let key = LoginAttempt::username_key(&user.username);
+        let mut attempt = self
+            .get_login_attempt_for_update(tx, &key)
+            .await?
+            .unwrap_or_else(|| LoginAttempt::new(key));
+        if attempt.is_locked(now) || attempt.is_backing_off(throttle, now) {
+            return Err(Error::TooManyRequests(
+                "too many failed login attempts, try again later".to_string(),
+            ));
+        }
+        if !self.verify_second_factor(tx, user, code).await? {
+            attempt.record_failure(throttle.max_failures, throttle.lockout_duration, now);
+            self.save_login_attempt(tx, &attempt).await?;
+            return Ok(false);
+        }
+        self.clear_login_attempt(tx, &attempt.key).await?;
+        Ok(true)
+    }
+
+    /// Starts a login that waits for the second factor of `user_id`.
+    pub async fn issue_login_challenge(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        expires_in: u64,
+    ) -> anyhow::Result<LoginResponse> {
+        let challenge_token = token::generate_token();
+        self.save_login_challenge(
+            tx,
+            user_id,
+            &token::hash_token(&challenge_token),
+            Utc::now() + Duration::seconds(expires_in as i64),
+        )
+        .await?;
+        Ok(LoginResponse::TwoFactorRequired {
+            challenge_token,
+            expires_in,
+        })
+    }
+
+    pub async fn save_login_challenge(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO login_challenges (id, user_id, token_hash, expires_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(user_id.to_string())
+        .bind(token_hash.to_string())
+        .bind(expires_at)
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_login_challenge_by_hash_for_update(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<LoginChallenge>> {
+        let challenge = sqlx::query_as::<_, LoginChallenge>(
+            r#"
+            SELECT * FROM login_challenges WHERE token_hash = $1 FOR UPDATE
+            "#,
+        )
+        .bind(token_hash.to_string())
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(challenge)
+    }
+
+    pub async fn record_login_challenge_failure(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE login_challenges SET failures = failures + 1 WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_login_challenge_used(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE login_challenges SET used_at = NOW() WHERE id = $1
+            "#,
+        )
+        .bind(id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
 use sqlx::{Postgres, Transaction};
 use uuid::Uuid;
 
-use crate::domain::blog::models::users::User;
+use crate::domain::blog::models::{
+    roles::{user_object, user_subject, Role},
+    users::User,
+};
 
 use super::postgres::Pg;
 
@@ -48,6 +51,57 @@ impl Pg {
         Ok(user)
     }
 
+    /// Locks the user row, so the user cannot be deleted until `tx` ends.
+    pub async fn get_user_by_username_for_update(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        username: &str,
+    ) -> anyhow::Result<Option<User>> {
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            SELECT * FROM users WHERE username = $1 FOR UPDATE
+            "#,
+        )
+        .bind(username)
+        .fetch_optional(tx.as_mut())
+        .await?;
+        Ok(user)
+    }
+
+    pub async fn get_users_by_email(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        email: &str,
+    ) -> anyhow::Result<Vec<User>> {
+        let users = sqlx::query_as::<_, User>(
+            r#"
+            SELECT * FROM users WHERE lower(email) = lower($1)
+            "#,
+        )
+        .bind(email)
+        .fetch_all(tx.as_mut())
+        .await?;
+        Ok(users)
+    }
+
+    pub async fn update_user_password(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: &str,
+        password: &str,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE users SET password = $1, updated_at = NOW() WHERE id = $2
+            "#,
+        )
+        .bind(password.to_string())
+        .bind(id.to_string())
+        .execute(tx.as_mut())
+        .await?;
+        Ok(())
+    }
+
     pub async fn find_user_by_id(
         &self,
         tx: &mut Transaction<'_, Postgres>,
@@ -80,8 +134,64 @@ impl Pg {
         Ok(())
     }
 
-    pub async fn remove_policy(&self, ptype: &str, params: Vec<String>) -> anyhow::Result<()> {
-        self.enforcer.remove_policy(ptype, params).await?;
+    /// Lets the user manage their own account and gives them `role`,
+    /// inside `tx`. Once `tx` commits, `load_user_policy` makes the rules
+    /// effective.
+    pub async fn save_user_policy(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        username: &str,
+        role: Role,
+    ) -> anyhow::Result<()> {
+        for (ptype, rule) in user_rules(username, role) {
+            self.save_casbin_rule(tx, ptype, &rule).await?;
+        }
         Ok(())
     }
+
+    /// Runs after the commit, so a failure cannot fail the request any more:
+    /// it is logged, and the committed rules take effect once the enforcer
+    /// is loaded from the database again, at the latest on restart.
+    pub async fn load_user_policy(&self, username: &str, role: Role) {
+        for (ptype, rule) in user_rules(username, role) {
+            if let Err(e) = self.enforcer.load_committed_policy(ptype, rule).await {
+                tracing::error!("failed to load the policy of user {}: {:?}", username, e);
+            }
+        }
+    }
+
+    /// Deletes every rule about the user inside `tx`: their own account
+    /// policy, roles and anything an admin granted them. Once `tx` commits,
+    /// `unload_user_policy` drops the rules from the enforcer.
+    pub async fn delete_user_policy(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        username: &str,
+    ) -> anyhow::Result<()> {
+        self.delete_casbin_rules_by_subject(tx, &user_subject(username))
+            .await
+    }
+
+    /// Runs after the commit; failures are logged like in `load_user_policy`.
+    pub async fn unload_user_policy(&self, username: &str) {
+        let subject = user_subject(username);
+        if let Err(e) = self.enforcer.unload_committed_subject(&subject).await {
+            tracing::error!("failed to unload the policy of user {}: {:?}", username, e);
+        }
+    }
+}
+
+fn user_rules(username: &str, role: Role) -> [(&'static str, Vec<String>); 2] {
+    [
+        ("p", own_account_policy(username)),
+        ("g", vec![user_subject(username), role.subject()]),
+    ]
+}
+
+fn own_account_policy(username: &str) -> Vec<String> {
+    vec![
+        user_subject(username),
+        user_object(username),
+        "(GET)|(POST)|(PUT)|(DELETE)".to_string(),
+    ]
 }
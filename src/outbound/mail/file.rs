@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::domain::blog::{error::Error, models::mail::Email, ports::Mailer};
+
+/// Writes every email to a file in `dir`, for local development and tests
+/// where nothing should leave the machine. Only the recipient and subject are
+/// logged, since bodies carry reset and verification tokens.
+#[derive(Debug, Clone)]
+pub struct FileMailer {
+    from: String,
+    dir: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(from: &str, dir: &str) -> Self {
+        Self {
+            from: from.to_string(),
+            dir: PathBuf::from(dir),
+        }
+    }
+}
+
+impl Mailer for FileMailer {
+    async fn send(&self, email: &Email) -> Result<(), Error> {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .with_context(|| format!("failed to create {}", self.dir.display()))?;
+        let path = self.dir.join(format!(
+            "{}-{}.eml",
+            Utc::now().format("%Y%m%dT%H%M%S"),
+            Uuid::new_v4()
+        ));
+        let content = format!(
+            "From: {}\nTo: {}\nSubject: {}\n\n{}",
+            self.from, email.to, email.subject, email.body
+        );
+        tokio::fs::write(&path, &content)
+            .await
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        tracing::info!(
+            "email \"{}\" to {} written to {}",
+            email.subject,
+            email.to,
+            path.display()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::Context;
+
+use crate::{
+    config::{MailSettings, MailTransport},
+    domain::blog::{error::Error, models::mail::Email, ports::Mailer},
+};
+
+use self::{file::FileMailer, smtp::SmtpMailer};
+
+pub mod file;
+pub mod smtp;
+
+/// The mailer selected by `mail.transport`.
+#[derive(Debug, Clone)]
+pub enum AnyMailer {
+    Smtp(SmtpMailer),
+    File(FileMailer),
+}
+
+impl AnyMailer {
+    pub fn new(config: &MailSettings) -> anyhow::Result<Self> {
+        let mailer = match config.transport {
+            MailTransport::Smtp => {
+                let smtp = config
+                    .smtp
+                    .as_ref()
+                    .context("mail.smtp is required by the smtp transport")?;
+                AnyMailer::Smtp(SmtpMailer::new(&config.from, smtp)?)
+            }
+            MailTransport::File => AnyMailer::File(FileMailer::new(&config.from, &config.file_dir)),
+        };
+        Ok(mailer)
+    }
+}
+
+impl Mailer for AnyMailer {
+    async fn send(&self, email: &Email) -> Result<(), Error> {
+        match self {
+            AnyMailer::Smtp(mailer) => mailer.send(email).await,
+            AnyMailer::File(mailer) => mailer.send(email).await,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use anyhow::Context;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+use crate::{
+    config::SmtpSettings,
+    domain::blog::{error::Error, models::mail::Email, ports::Mailer},
+};
+
+#[derive(Debug, Clone)]
+pub struct SmtpMailer {
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    pub fn new(from: &str, config: &SmtpSettings) -> anyhow::Result<Self> {
+        let from = from.parse().context("invalid mail.from address")?;
+        let mut builder = if config.starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                .context("failed to set up smtp transport")?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+        }
+        .port(config.port);
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        Ok(Self {
+            from,
+            transport: builder.build(),
+        })
+    }
+}
+
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: &Email) -> Result<(), Error> {
+        let to: Mailbox = email
+            .to
+            .parse()
+            .with_context(|| format!("invalid recipient {}", email.to))?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&email.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(email.body.clone())
+            .context("failed to build email")?;
+        self.transport
+            .send(message)
+            .await
+            .context("failed to send email")?;
+        Ok(())
+    }
+}
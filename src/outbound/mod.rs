@@ -1 +1,3 @@
 pub mod db;
+pub mod mail;
+pub mod oidc;
@@ -0,0 +1,251 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use reqwest::Url;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+use crate::{
+    config::OidcSettings,
+    domain::blog::{
+        error::Error,
+        models::oidc::{ExternalIdentity, OidcLoginState},
+        ports::IdentityProvider,
+    },
+};
+
+/// Signing algorithms accepted on ID tokens. Symmetric ones are left out on
+/// purpose: they would be keyed with our own client secret.
+const ID_TOKEN_ALGORITHMS: [Algorithm; 7] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+/// The parts of the provider's discovery document we use.
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    preferred_username: Option<String>,
+}
+
+/// OpenID Connect client for `auth.oidc`. Discovery and the provider's keys
+/// are fetched on first use and cached; the keys are fetched again when a
+/// token names one we have not seen, so the provider can rotate them.
+/// Without settings every flow fails with `NotFound`.
+#[derive(Debug, Clone)]
+pub struct OidcClient(Option<Arc<Inner>>);
+
+#[derive(Debug)]
+struct Inner {
+    settings: OidcSettings,
+    http: reqwest::Client,
+    metadata: RwLock<Option<ProviderMetadata>>,
+    jwks: RwLock<Option<JwkSet>>,
+}
+
+impl OidcClient {
+    pub fn new(settings: Option<&OidcSettings>) -> anyhow::Result<Self> {
+        let Some(settings) = settings else {
+            return Ok(Self(None));
+        };
+        let http = reqwest::Client::builder()
+            .build()
+            .context("failed to build the oidc http client")?;
+        Ok(Self(Some(Arc::new(Inner {
+            settings: settings.clone(),
+            http,
+            metadata: RwLock::new(None),
+            jwks: RwLock::new(None),
+        }))))
+    }
+
+    fn inner(&self) -> Result<&Inner, Error> {
+        self.0
+            .as_deref()
+            .ok_or_else(|| Error::NotFound("single sign-on is not configured".to_string()))
+    }
+}
+
+impl Inner {
+    async fn metadata(&self) -> anyhow::Result<ProviderMetadata> {
+        if let Some(metadata) = self.metadata.read().await.as_ref() {
+            return Ok(metadata.clone());
+        }
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            self.settings.issuer.trim_end_matches('/')
+        );
+        let metadata: ProviderMetadata = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .with_context(|| format!("failed to fetch {url}"))?
+            .json()
+            .await
+            .context("invalid oidc discovery document")?;
+        if metadata.issuer != self.settings.issuer {
+            anyhow::bail!(
+                "provider claims issuer {}, expected {}",
+                metadata.issuer,
+                self.settings.issuer
+            );
+        }
+        *self.metadata.write().await = Some(metadata.clone());
+        Ok(metadata)
+    }
+
+    async fn fetch_jwks(&self, jwks_uri: &str) -> anyhow::Result<JwkSet> {
+        let jwks: JwkSet = self
+            .http
+            .get(jwks_uri)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .with_context(|| format!("failed to fetch {jwks_uri}"))?
+            .json()
+            .await
+            .context("invalid provider jwks")?;
+        *self.jwks.write().await = Some(jwks.clone());
+        Ok(jwks)
+    }
+
+    async fn decoding_key(
+        &self,
+        kid: Option<&str>,
+        metadata: &ProviderMetadata,
+    ) -> Result<DecodingKey, Error> {
+        let find = |jwks: &JwkSet| match kid {
+            Some(kid) => jwks.find(kid).cloned(),
+            None if jwks.keys.len() == 1 => jwks.keys.first().cloned(),
+            None => None,
+        };
+        let cached = self.jwks.read().await.as_ref().and_then(find);
+        let jwk = match cached {
+            Some(jwk) => jwk,
+            None => find(&self.fetch_jwks(&metadata.jwks_uri).await?).ok_or_else(|| {
+                Error::Unauthorized(format!("id token signed with unknown key {kid:?}"))
+            })?,
+        };
+        DecodingKey::from_jwk(&jwk)
+            .map_err(|e| Error::Unauthorized(format!("unusable provider key: {e}")))
+    }
+
+    async fn validate_id_token(
+        &self,
+        id_token: &str,
+        nonce: &str,
+        metadata: &ProviderMetadata,
+    ) -> Result<IdTokenClaims, Error> {
+        let header = decode_header(id_token)
+            .map_err(|e| Error::Unauthorized(format!("invalid id token: {e}")))?;
+        if !ID_TOKEN_ALGORITHMS.contains(&header.alg) {
+            return Err(Error::Unauthorized(format!(
+                "id token signed with unsupported algorithm {:?}",
+                header.alg
+            )));
+        }
+        let key = self.decoding_key(header.kid.as_deref(), metadata).await?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_audience(&[&self.settings.client_id]);
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|e| Error::Unauthorized(format!("invalid id token: {e}")))?
+            .claims;
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(Error::Unauthorized("id token nonce mismatch".to_string()));
+        }
+        Ok(claims)
+    }
+}
+
+impl IdentityProvider for OidcClient {
+    async fn authorization_url(&self, state: &OidcLoginState) -> Result<String, Error> {
+        let inner = self.inner()?;
+        let metadata = inner.metadata().await?;
+        let settings = &inner.settings;
+        let url = Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", settings.client_id.as_str()),
+                ("redirect_uri", settings.redirect_uri.as_str()),
+                ("scope", settings.scopes.join(" ").as_str()),
+                ("state", state.state.as_str()),
+                ("nonce", state.nonce.as_str()),
+                ("code_challenge", state.code_challenge().as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .context("invalid authorization endpoint")?;
+        Ok(url.to_string())
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        state: &OidcLoginState,
+    ) -> Result<ExternalIdentity, Error> {
+        let inner = self.inner()?;
+        let metadata = inner.metadata().await?;
+        let settings = &inner.settings;
+        let res = inner
+            .http
+            .post(&metadata.token_endpoint)
+            .basic_auth(&settings.client_id, Some(&settings.client_secret))
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", settings.redirect_uri.as_str()),
+                ("client_id", settings.client_id.as_str()),
+                ("code_verifier", state.code_verifier.as_str()),
+            ])
+            .send()
+            .await
+            .context("failed to reach the token endpoint")?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            tracing::warn!("oidc token exchange failed with {status}: {body}");
+            return Err(Error::Unauthorized(
+                "authorization code was rejected".to_string(),
+            ));
+        }
+        let tokens: TokenResponse = res.json().await.context("invalid token response")?;
+        let claims = inner
+            .validate_id_token(&tokens.id_token, &state.nonce, &metadata)
+            .await?;
+        Ok(ExternalIdentity {
+            issuer: claims.iss,
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified,
+            preferred_username: claims.preferred_username,
+        })
+    }
+}
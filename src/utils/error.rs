@@ -6,4 +6,6 @@ pub enum Error {
     PasswordHashError(#[from] argon2::password_hash::Error),
     #[error(transparent)]
     JwtTokenError(#[from] jsonwebtoken::errors::Error),
+    #[error("invalid jwt key: {0}")]
+    JwtKeyError(String),
 }
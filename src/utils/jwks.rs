@@ -0,0 +1,120 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::Serialize;
+use simple_asn1::{from_der, ASN1Block};
+
+use crate::config::JwtAlgorithm;
+
+use super::Error;
+
+/// A public key in JSON Web Key format (RFC 7517).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Jwk {
+    pub kty: &'static str,
+    pub kid: String,
+    pub alg: &'static str,
+    #[serde(rename = "use")]
+    pub key_use: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+impl Jwk {
+    /// Builds the JWK of a PEM encoded public key.
+    pub fn from_public_pem(kid: &str, algorithm: JwtAlgorithm, pem: &str) -> Result<Self, Error> {
+        let pem = pem::parse(pem).map_err(|err| Error::JwtKeyError(err.to_string()))?;
+        let mut jwk = Self {
+            kty: "",
+            kid: kid.to_string(),
+            alg: "",
+            key_use: "sig",
+            crv: None,
+            n: None,
+            e: None,
+            x: None,
+            y: None,
+        };
+        match algorithm {
+            JwtAlgorithm::RS256 => {
+                // PKCS#1 keys are the bare RSAPublicKey, SPKI wraps it
+                let rsa_key = if pem.tag() == "RSA PUBLIC KEY" {
+                    pem.contents().to_vec()
+                } else {
+                    spki_public_key(pem.contents())?
+                };
+                let (n, e) = rsa_modulus_and_exponent(&rsa_key)?;
+                jwk.kty = "RSA";
+                jwk.alg = "RS256";
+                jwk.n = Some(URL_SAFE_NO_PAD.encode(n));
+                jwk.e = Some(URL_SAFE_NO_PAD.encode(e));
+            }
+            JwtAlgorithm::ES256 => {
+                // an uncompressed point: 0x04 || x || y
+                let point = spki_public_key(pem.contents())?;
+                if point.len() != 65 || point[0] != 0x04 {
+                    return Err(Error::JwtKeyError(format!(
+                        "key {kid} is not an uncompressed P-256 public key"
+                    )));
+                }
+                jwk.kty = "EC";
+                jwk.alg = "ES256";
+                jwk.crv = Some("P-256");
+                jwk.x = Some(URL_SAFE_NO_PAD.encode(&point[1..33]));
+                jwk.y = Some(URL_SAFE_NO_PAD.encode(&point[33..]));
+            }
+            JwtAlgorithm::EdDSA => {
+                let key = spki_public_key(pem.contents())?;
+                if key.len() != 32 {
+                    return Err(Error::JwtKeyError(format!(
+                        "key {kid} is not an Ed25519 public key"
+                    )));
+                }
+                jwk.kty = "OKP";
+                jwk.alg = "EdDSA";
+                jwk.crv = Some("Ed25519");
+                jwk.x = Some(URL_SAFE_NO_PAD.encode(key));
+            }
+        }
+        Ok(jwk)
+    }
+}
+
+/// Extracts the `subjectPublicKey` bits of a DER SubjectPublicKeyInfo.
+fn spki_public_key(der: &[u8]) -> Result<Vec<u8>, Error> {
+    let blocks = from_der(der).map_err(|err| Error::JwtKeyError(err.to_string()))?;
+    match blocks.first() {
+        Some(ASN1Block::Sequence(_, items)) => match items.get(1) {
+            Some(ASN1Block::BitString(_, _, bits)) => Ok(bits.clone()),
+            _ => Err(Error::JwtKeyError("missing subject public key".to_string())),
+        },
+        _ => Err(Error::JwtKeyError(
+            "not a SubjectPublicKeyInfo structure".to_string(),
+        )),
+    }
+}
+
+/// Extracts the big-endian modulus and public exponent of a DER RSAPublicKey.
+fn rsa_modulus_and_exponent(der: &[u8]) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let blocks = from_der(der).map_err(|err| Error::JwtKeyError(err.to_string()))?;
+    match blocks.first() {
+        Some(ASN1Block::Sequence(_, items)) => match items.as_slice() {
+            [ASN1Block::Integer(_, n), ASN1Block::Integer(_, e)] => {
+                Ok((n.to_bytes_be().1, e.to_bytes_be().1))
+            }
+            _ => Err(Error::JwtKeyError("malformed RSA public key".to_string())),
+        },
+        _ => Err(Error::JwtKeyError("not an RSA public key".to_string())),
+    }
+}
@@ -1,38 +1,136 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
 use jsonwebtoken::{
-    decode, encode, get_current_timestamp, Algorithm, DecodingKey, EncodingKey, Header, TokenData,
-    Validation,
+    decode, decode_header, encode, get_current_timestamp, Algorithm, DecodingKey, EncodingKey,
+    Header, TokenData, Validation,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use uuid::Uuid;
+
+use crate::config::{AuthSettings, JwtAlgorithm};
 
-use super::Error;
+use super::{
+    jwks::{Jwk, JwkSet},
+    Error,
+};
 
 const JWT_ALGORITHM: Algorithm = Algorithm::HS512;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserClaims {
     pub pid: String,
-    exp: u64,
+    /// Unique id of the token, used to revoke it before it expires.
+    pub jti: String,
+    pub iat: u64,
+    pub exp: u64,
     pub claims: Option<Value>,
 }
 
-#[derive(Debug)]
-pub struct JWT {
-    secret: String,
+/// Signs tokens with a single key and verifies them against every configured
+/// key, picked by the `kid` header. Cheap to clone.
+#[derive(Clone)]
+pub struct JWT(Arc<Keys>);
+
+struct Keys {
+    signing: Option<SigningKey>,
+    verifying: HashMap<Option<String>, VerifyingKey>,
+    jwks: JwkSet,
+}
+
+struct SigningKey {
+    kid: Option<String>,
     algorithm: Algorithm,
+    key: EncodingKey,
+}
+
+struct VerifyingKey {
+    algorithm: Algorithm,
+    key: DecodingKey,
 }
 
 impl JWT {
-    pub fn new(secret: &str) -> Self {
-        Self {
-            secret: secret.to_string(),
+    /// Signs and verifies with a shared HS512 secret, without a `kid`.
+    pub fn new(secret: &str) -> Result<Self, Error> {
+        let signing = SigningKey {
+            kid: None,
             algorithm: JWT_ALGORITHM,
-        }
+            key: EncodingKey::from_base64_secret(secret)?,
+        };
+        let verifying = VerifyingKey {
+            algorithm: JWT_ALGORITHM,
+            key: DecodingKey::from_base64_secret(secret)?,
+        };
+        Ok(Self(Arc::new(Keys {
+            signing: Some(signing),
+            verifying: HashMap::from([(None, verifying)]),
+            jwks: JwkSet::default(),
+        })))
     }
 
-    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
-        self.algorithm = algorithm;
-        self
+    /// Loads the asymmetric keys from `settings`, falling back to the shared
+    /// secret when none are configured.
+    pub fn from_settings(settings: &AuthSettings) -> Result<Self, Error> {
+        if settings.keys.is_empty() {
+            return Self::new(&settings.secret);
+        }
+        let mut signing = None;
+        let mut verifying = HashMap::new();
+        let mut jwks = JwkSet::default();
+        for key in &settings.keys {
+            let algorithm = match key.algorithm {
+                JwtAlgorithm::RS256 => Algorithm::RS256,
+                JwtAlgorithm::ES256 => Algorithm::ES256,
+                JwtAlgorithm::EdDSA => Algorithm::EdDSA,
+            };
+            let public_key = key.public_key.as_bytes();
+            let decoding_key = match key.algorithm {
+                JwtAlgorithm::RS256 => DecodingKey::from_rsa_pem(public_key)?,
+                JwtAlgorithm::ES256 => DecodingKey::from_ec_pem(public_key)?,
+                JwtAlgorithm::EdDSA => DecodingKey::from_ed_pem(public_key)?,
+            };
+            verifying.insert(
+                Some(key.kid.clone()),
+                VerifyingKey {
+                    algorithm,
+                    key: decoding_key,
+                },
+            );
+            jwks.keys.push(Jwk::from_public_pem(
+                &key.kid,
+                key.algorithm,
+                &key.public_key,
+            )?);
+
+            if settings.signing_kid.as_ref() != Some(&key.kid) {
+                continue;
+            }
+            let private_key = key.private_key.as_ref().ok_or_else(|| {
+                Error::JwtKeyError(format!("signing key {} has no private key", key.kid))
+            })?;
+            let private_key = private_key.as_bytes();
+            let encoding_key = match key.algorithm {
+                JwtAlgorithm::RS256 => EncodingKey::from_rsa_pem(private_key)?,
+                JwtAlgorithm::ES256 => EncodingKey::from_ec_pem(private_key)?,
+                JwtAlgorithm::EdDSA => EncodingKey::from_ed_pem(private_key)?,
+            };
+            signing = Some(SigningKey {
+                kid: Some(key.kid.clone()),
+                algorithm,
+                key: encoding_key,
+            });
+        }
+        if signing.is_none() {
+            return Err(Error::JwtKeyError(format!(
+                "signing key {:?} is not among the configured keys",
+                settings.signing_kid
+            )));
+        }
+        Ok(Self(Arc::new(Keys {
+            signing,
+            verifying,
+            jwks,
+        })))
     }
 
     pub fn generate_token(
@@ -41,27 +139,55 @@ impl JWT {
         pid: String,
         claims: Option<Value>,
     ) -> Result<String, Error> {
-        let exp = get_current_timestamp().saturating_add(*expiration);
+        let signing = self
+            .0
+            .signing
+            .as_ref()
+            .ok_or_else(|| Error::JwtKeyError("no signing key configured".to_string()))?;
+        let iat = get_current_timestamp();
+        let exp = iat.saturating_add(*expiration);
+        let jti = Uuid::new_v4().to_string();
 
-        let claims = UserClaims { pid, exp, claims };
+        let claims = UserClaims {
+            pid,
+            jti,
+            iat,
+            exp,
+            claims,
+        };
 
-        let token = encode(
-            &Header::new(self.algorithm),
-            &claims,
-            &EncodingKey::from_base64_secret(&self.secret)?,
-        )?;
+        let mut header = Header::new(signing.algorithm);
+        header.kid = signing.kid.clone();
+        let token = encode(&header, &claims, &signing.key)?;
         Ok(token)
     }
 
     pub fn validate(&self, token: &str) -> Result<TokenData<UserClaims>, Error> {
-        let mut validate = Validation::new(self.algorithm);
+        let header = decode_header(token)?;
+        let verifying = self
+            .0
+            .verifying
+            .get(&header.kid)
+            .ok_or_else(|| Error::JwtKeyError(format!("unknown key id {:?}", header.kid)))?;
+        let mut validate = Validation::new(verifying.algorithm);
         validate.leeway = 0;
 
-        let res = decode::<UserClaims>(
-            token,
-            &DecodingKey::from_base64_secret(&self.secret)?,
-            &validate,
-        )?;
+        let res = decode::<UserClaims>(token, &verifying.key, &validate)?;
         Ok(res)
     }
+
+    /// Public verification keys, for publishing at `/.well-known/jwks.json`.
+    /// Empty when tokens are signed with the shared secret.
+    pub fn jwks(&self) -> &JwkSet {
+        &self.0.jwks
+    }
+}
+
+impl fmt::Debug for JWT {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JWT")
+            .field("signing_kid", &self.0.signing.as_ref().map(|key| &key.kid))
+            .field("keys", &self.0.verifying.len())
+            .finish()
+    }
 }
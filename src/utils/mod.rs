@@ -1,6 +1,11 @@
 pub mod error;
+pub mod jwks;
 pub mod jwt;
 pub mod password_hash;
+pub mod slug;
+pub mod token;
+pub mod totp;
 
 pub use error::Error;
-pub use password_hash::{compute_password_hash, verify_password_hash};
+pub use password_hash::{compute_password_hash, verify_dummy_password_hash, verify_password_hash};
+pub use slug::slugify;
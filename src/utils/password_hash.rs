@@ -1,7 +1,15 @@
+use std::sync::LazyLock;
+
 use argon2::{password_hash::SaltString, Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 
 use super::Error;
 
+/// Checked against when a login names an unknown user, so that takes as long
+/// as a wrong password.
+static DUMMY_PASSWORD_HASH: LazyLock<String> = LazyLock::new(|| {
+    compute_password_hash("dummy password").expect("failed to hash dummy password")
+});
+
 pub fn compute_password_hash(password: &str) -> Result<String, Error> {
     let slat = SaltString::generate(&mut rand::thread_rng());
     let password = Argon2::default()
@@ -18,3 +26,8 @@ pub fn verify_password_hash(
     Argon2::default().verify_password(password_candidate.as_bytes(), &expected_password_hash)?;
     Ok(())
 }
+
+/// Burns the same time as `verify_password_hash` without a real hash.
+pub fn verify_dummy_password_hash(password_candidate: String) {
+    let _ = verify_password_hash(DUMMY_PASSWORD_HASH.clone(), password_candidate);
+}
@@ -0,0 +1,18 @@
+const DEFAULT_SLUG: &str = "post";
+
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::with_capacity(title.len());
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        DEFAULT_SLUG.to_string()
+    } else {
+        slug.to_string()
+    }
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use blog_rs::domain::blog::{
+    error::Error,
+    models::{
+        access_tokens::{CreateAccessTokenRequest, TokenScope},
+        auth::{ChangePasswordRequest, LogoutAllRequest},
+        roles::Role,
+        users::User,
+    },
+    ports::BlogService,
+};
+use sqlx::PgPool;
+
+async fn create_access_token<BS: BlogService>(service: &BS, user: &User) -> String {
+    let req = CreateAccessTokenRequest::new(
+        user.id.clone(),
+        "ci".to_string(),
+        vec![TokenScope::PostsRead],
+        None,
+    )
+    .unwrap();
+    service.create_access_token(&req).await.unwrap().secret
+}
+
+#[sqlx::test]
+async fn logout_all_revokes_access_tokens(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    let user = common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+    let secret = create_access_token(&service, &user).await;
+    assert!(service.authenticate_access_token(&secret).await.is_ok());
+
+    let req = LogoutAllRequest::new(user.id.clone()).unwrap();
+    service.logout_all(&req).await.unwrap();
+    let res = service.authenticate_access_token(&secret).await;
+    assert!(matches!(res, Err(Error::Unauthorized(_))));
+}
+
+#[sqlx::test]
+async fn changing_the_password_revokes_access_tokens(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    let user = common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+    let secret = create_access_token(&service, &user).await;
+
+    let req = ChangePasswordRequest::new(
+        user.id.clone(),
+        common::PASSWORD.to_string(),
+        "another password".to_string(),
+    )
+    .unwrap();
+    service.change_password(&req).await.unwrap();
+    let res = service.authenticate_access_token(&secret).await;
+    assert!(matches!(res, Err(Error::Unauthorized(_))));
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use blog_rs::{
+    domain::blog::{
+        error::Error,
+        models::{
+            auth::{LoginResponse, LogoutRequest, RefreshTokenRequest, TokenPair},
+            roles::Role,
+        },
+        ports::{BlogRepository, BlogService},
+    },
+    utils::jwt::UserClaims,
+};
+use jsonwebtoken::{encode, get_current_timestamp, Algorithm, EncodingKey, Header};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+fn token(secret: &str, pid: &str, iat: u64, exp: u64) -> String {
+    let claims = UserClaims {
+        pid: pid.to_string(),
+        jti: uuid::Uuid::new_v4().to_string(),
+        iat,
+        exp,
+        claims: None,
+    };
+    encode(
+        &Header::new(Algorithm::HS512),
+        &claims,
+        &EncodingKey::from_base64_secret(secret).unwrap(),
+    )
+    .unwrap()
+}
+
+#[sqlx::test]
+async fn expired_access_token_is_unauthorized(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    let user = common::create_user(&pg, "alice", Role::Author).await;
+    let config = common::config();
+    let secret = config.auth.secret.clone();
+    let app = common::spawn_app(common::service(pg), config).await;
+    let client = reqwest::Client::new();
+    let now = get_current_timestamp();
+
+    let res = client
+        .get(format!("{app}/api/auth/tokens"))
+        .bearer_auth(token(&secret, &user.id, now - 120, now - 60))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = client
+        .get(format!("{app}/api/auth/tokens"))
+        .bearer_auth(token(&secret, &user.id, now, now + 60))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[sqlx::test]
+async fn malformed_and_forged_tokens_are_unauthorized(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    let user = common::create_user(&pg, "alice", Role::Author).await;
+    let app = common::spawn_app(common::service(pg), common::config()).await;
+    let client = reqwest::Client::new();
+    let now = get_current_timestamp();
+
+    for token in [
+        "not-a-jwt".to_string(),
+        token("c29tZSBvdGhlciBzZWNyZXQ=", &user.id, now, now + 60),
+    ] {
+        let res = client
+            .get(format!("{app}/api/auth/tokens"))
+            .bearer_auth(token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[sqlx::test]
+async fn tokens_issued_right_after_logout_all_are_accepted(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let app = common::spawn_app(common::service(pg), common::config()).await;
+    let client = reqwest::Client::new();
+
+    let token = common::login(&client, &app, "alice").await;
+    let res = client
+        .post(format!("{app}/api/auth/logout-all"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let token = common::login(&client, &app, "alice").await;
+    let res = client
+        .get(format!("{app}/api/auth/tokens"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+async fn refresh<BS: BlogService>(service: &BS, refresh_token: &str) -> Result<TokenPair, Error> {
+    let req = RefreshTokenRequest::new(refresh_token.to_string(), common::jwt(), 900, 3600, false)
+        .unwrap();
+    service.refresh_token(&req).await
+}
+
+#[sqlx::test]
+async fn refresh_tokens_rotate_and_reuse_revokes_the_family(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+    let req = common::login_request("alice", common::PASSWORD, common::login_policy(false));
+    let LoginResponse::Authenticated(first) = service.login(&req).await.unwrap() else {
+        panic!("expected tokens");
+    };
+    // A second session is a separate family and survives the reuse below.
+    let LoginResponse::Authenticated(other) = service.login(&req).await.unwrap() else {
+        panic!("expected tokens");
+    };
+
+    let second = refresh(&service, &first.refresh_token).await.unwrap();
+    assert_ne!(second.refresh_token, first.refresh_token);
+    assert_ne!(second.access_token, first.access_token);
+    let third = refresh(&service, &second.refresh_token).await.unwrap();
+
+    // Presenting a rotated token again revokes every token of its family.
+    let res = refresh(&service, &first.refresh_token).await;
+    assert!(matches!(res, Err(Error::Unauthorized(_))));
+    let res = refresh(&service, &third.refresh_token).await;
+    assert!(matches!(res, Err(Error::Unauthorized(_))));
+
+    assert!(refresh(&service, &other.refresh_token).await.is_ok());
+    let res = refresh(&service, "not-a-refresh-token").await;
+    assert!(matches!(res, Err(Error::Unauthorized(_))));
+}
+
+#[sqlx::test]
+async fn logouts_on_one_instance_apply_to_the_others_at_once(pool: PgPool) {
+    let user = common::create_user(&common::pg(pool.clone()).await, "alice", Role::Author).await;
+    let first = common::pg(pool.clone()).await;
+    let second = common::pg(pool).await;
+    let jti = uuid::Uuid::new_v4().to_string();
+
+    assert!(!BlogRepository::is_token_revoked(&second, &jti)
+        .await
+        .unwrap());
+    let exp = get_current_timestamp() + 60;
+    let req = LogoutRequest::new(jti.clone(), user.id, exp, None).unwrap();
+    BlogRepository::logout(&first, &req).await.unwrap();
+    assert!(BlogRepository::is_token_revoked(&second, &jti)
+        .await
+        .unwrap());
+    assert!(BlogRepository::is_token_revoked(&first, &jti)
+        .await
+        .unwrap());
+}
@@ -0,0 +1,293 @@
+#![allow(dead_code)]
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use blog_rs::{
+    config::{get_config, Settings},
+    domain::blog::{
+        error::Error,
+        models::{
+            auth::{LoginPolicy, LoginThrottle},
+            mail::Email,
+            oidc::{ExternalIdentity, OidcLoginState},
+            posts::{CreatePostRequest, Post},
+            roles::Role,
+            two_factor::{ConfirmTotpRequest, EnrollTotpRequest},
+            users::{CreateUserRequest, LoginRequest, User},
+        },
+        ports::{BlogRepository, BlogService, IdentityProvider, Mailer},
+        service::Service,
+    },
+    inbound::http::http_server,
+    outbound::{db::postgres::Pg, oidc::OidcClient},
+    utils::jwt::JWT,
+};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sqlx::PgPool;
+use tokio::net::TcpListener;
+
+pub const PASSWORD: &str = "password123";
+
+pub async fn pg(pool: PgPool) -> Pg {
+    Pg::from_pool(pool)
+        .await
+        .expect("failed to build repository")
+}
+
+pub async fn create_user(pg: &Pg, username: &str, role: Role) -> User {
+    let req = CreateUserRequest::new(
+        username.to_string(),
+        Some(format!("{username}@example.com")),
+        None,
+        PASSWORD.to_string(),
+        false,
+        3600,
+        role,
+    )
+    .unwrap();
+    BlogRepository::create_user(pg, &req).await.unwrap()
+}
+
+pub async fn create_post(pg: &Pg, username: &str, title: &str, content: &str) -> Post {
+    let req = CreatePostRequest::new(
+        title.to_string(),
+        content.to_string(),
+        username.to_string(),
+        None,
+        vec![],
+    )
+    .unwrap();
+    BlogRepository::create_post(pg, &req).await.unwrap()
+}
+
+/// `casbin_rule` rows whose subject is `subject`.
+pub async fn casbin_rule_count(pool: &PgPool, subject: &str) -> i64 {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM casbin_rule WHERE v0 = $1")
+        .bind(subject)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    count
+}
+
+pub async fn user_count(pool: &PgPool, username: &str) -> i64 {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE username = $1")
+        .bind(username)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    count
+}
+
+/// Keeps sent emails in memory so tests can read them.
+#[derive(Debug, Clone, Default)]
+pub struct TestMailer {
+    sent: Arc<Mutex<Vec<Email>>>,
+    failing: bool,
+    hanging: bool,
+}
+
+impl TestMailer {
+    /// A mailer whose every send fails, like an unreachable SMTP server.
+    pub fn failing() -> Self {
+        Self {
+            failing: true,
+            ..Self::default()
+        }
+    }
+
+    /// A mailer whose sends never complete, like a stalled SMTP server.
+    pub fn hanging() -> Self {
+        Self {
+            hanging: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn sent(&self) -> Vec<Email> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    /// Waits for emails sent in the background.
+    pub async fn wait_for(&self, count: usize) -> Vec<Email> {
+        for _ in 0..100 {
+            let sent = self.sent();
+            if sent.len() >= count {
+                return sent;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("expected {count} emails, got {}", self.sent().len());
+    }
+}
+
+impl Mailer for TestMailer {
+    async fn send(&self, email: &Email) -> Result<(), Error> {
+        if self.failing {
+            return Err(Error::Unknown(anyhow::anyhow!("mail server unreachable")));
+        }
+        if self.hanging {
+            std::future::pending::<()>().await;
+        }
+        self.sent.lock().unwrap().push(email.clone());
+        Ok(())
+    }
+}
+
+pub fn config() -> Settings {
+    get_config().expect("failed to read config")
+}
+
+pub fn service(pg: Pg) -> Service<Pg, TestMailer, OidcClient> {
+    service_with_mailer(pg, TestMailer::default())
+}
+
+pub fn service_with_mailer(pg: Pg, mailer: TestMailer) -> Service<Pg, TestMailer, OidcClient> {
+    Service::new(pg, mailer, OidcClient::new(None).unwrap())
+}
+
+/// Stands in for the OpenID Connect provider, vouching for `identity` on
+/// every login.
+#[derive(Debug, Clone)]
+pub struct TestIdentityProvider(pub ExternalIdentity);
+
+impl TestIdentityProvider {
+    pub fn new(subject: &str, email: &str, email_verified: bool) -> Self {
+        Self(ExternalIdentity {
+            issuer: "https://idp.example.com".to_string(),
+            subject: subject.to_string(),
+            email: Some(email.to_string()),
+            email_verified,
+            preferred_username: None,
+        })
+    }
+}
+
+impl IdentityProvider for TestIdentityProvider {
+    async fn authorization_url(&self, state: &OidcLoginState) -> Result<String, Error> {
+        Ok(format!(
+            "https://idp.example.com/authorize?state={}",
+            state.state
+        ))
+    }
+
+    async fn exchange_code(
+        &self,
+        _code: &str,
+        _state: &OidcLoginState,
+    ) -> Result<ExternalIdentity, Error> {
+        Ok(self.0.clone())
+    }
+}
+
+pub fn oidc_service(
+    pg: Pg,
+    identity_provider: TestIdentityProvider,
+) -> Service<Pg, TestMailer, TestIdentityProvider> {
+    Service::new(pg, TestMailer::default(), identity_provider)
+}
+
+/// Serves the application on a random local port and returns its base URL.
+pub async fn spawn_app<BS: BlogService>(blog_service: BS, config: Settings) -> String {
+    let router = http_server::router(blog_service, config).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+    format!("http://{addr}")
+}
+
+/// Logs in over HTTP and returns the access token.
+pub async fn login(client: &reqwest::Client, app: &str, username: &str) -> String {
+    let res: serde_json::Value = client
+        .post(format!("{app}/api/auth/login"))
+        .json(&serde_json::json!({ "username": username, "password": PASSWORD }))
+        .send()
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    res["data"]["token"].as_str().unwrap().to_string()
+}
+
+pub fn jwt() -> JWT {
+    JWT::from_settings(&config().auth).unwrap()
+}
+
+pub fn login_policy(require_verified_email: bool) -> LoginPolicy {
+    let config = config();
+    let throttle = &config.auth.login_throttle;
+    LoginPolicy {
+        require_verified_email,
+        throttle: LoginThrottle {
+            max_failures: throttle.max_failures,
+            ip_max_failures: throttle.ip_max_failures,
+            backoff_base: 0,
+            backoff_max: 0,
+            lockout_duration: throttle.lockout_duration,
+        },
+        challenge_expiration: config.auth.two_factor.challenge_expiration,
+    }
+}
+
+pub fn login_request(username: &str, password: &str, policy: LoginPolicy) -> LoginRequest {
+    LoginRequest::new(
+        username.to_string(),
+        password.to_string(),
+        jwt(),
+        900,
+        3600,
+        None,
+        policy,
+    )
+    .unwrap()
+}
+
+/// The TOTP code an authenticator app would show for `secret` right now.
+pub fn totp_code(secret: &str) -> String {
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).unwrap();
+    let step = chrono::Utc::now().timestamp() as u64 / 30;
+    let mut mac = Hmac::<Sha1>::new_from_slice(&key).unwrap();
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    format!("{:06}", binary % 1_000_000)
+}
+
+/// Turns on TOTP for `user` and returns their recovery codes.
+pub async fn enable_two_factor<BS: BlogService>(service: &BS, user: &User) -> Vec<String> {
+    let enrollment = service
+        .enroll_totp(&EnrollTotpRequest::new(user.id.clone(), "blog-rs".to_string()).unwrap())
+        .await
+        .unwrap();
+    let req = ConfirmTotpRequest::new(user.id.clone(), totp_code(&enrollment.secret), 10).unwrap();
+    service.confirm_totp(&req).await.unwrap()
+}
+
+pub async fn verify_email(pool: &PgPool, username: &str) {
+    sqlx::query("UPDATE users SET email_verified_at = NOW() WHERE username = $1")
+        .bind(username)
+        .execute(pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use blog_rs::domain::blog::{
+    error::Error,
+    models::{
+        auth::{LoginResponse, RefreshTokenRequest},
+        roles::Role,
+        two_factor::VerifyTwoFactorRequest,
+    },
+    ports::BlogService,
+};
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn login_refuses_unverified_accounts(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+
+    let req = common::login_request("alice", common::PASSWORD, common::login_policy(true));
+    let res = service.login(&req).await;
+    assert!(matches!(res, Err(Error::Forbidden(_))));
+}
+
+#[sqlx::test]
+async fn refresh_refuses_unverified_accounts(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+
+    let req = common::login_request("alice", common::PASSWORD, common::login_policy(false));
+    let LoginResponse::Authenticated(tokens) = service.login(&req).await.unwrap() else {
+        panic!("expected tokens");
+    };
+    let refresh = |require_verified_email| {
+        RefreshTokenRequest::new(
+            tokens.refresh_token.clone(),
+            common::jwt(),
+            900,
+            3600,
+            require_verified_email,
+        )
+        .unwrap()
+    };
+    let res = service.refresh_token(&refresh(true)).await;
+    assert!(matches!(res, Err(Error::Forbidden(_))));
+    // The refused attempt must not have used up the refresh token.
+    assert!(service.refresh_token(&refresh(false)).await.is_ok());
+}
+
+#[sqlx::test]
+async fn two_factor_verification_refuses_unverified_accounts(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    let user = common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+    let recovery_codes = common::enable_two_factor(&service, &user).await;
+
+    let req = common::login_request("alice", common::PASSWORD, common::login_policy(false));
+    let LoginResponse::TwoFactorRequired {
+        challenge_token, ..
+    } = service.login(&req).await.unwrap()
+    else {
+        panic!("expected a two-factor challenge");
+    };
+    let req = VerifyTwoFactorRequest::new(
+        challenge_token,
+        recovery_codes[0].clone(),
+        common::jwt(),
+        900,
+        3600,
+        common::login_policy(true),
+    )
+    .unwrap();
+    let res = service.verify_two_factor(&req).await;
+    assert!(matches!(res, Err(Error::Forbidden(_))));
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use blog_rs::domain::blog::{
+    error::Error,
+    models::{
+        posts::UpdatePostRequest,
+        roles::Role,
+        users::{CreateUserRequest, GetUserRequest},
+    },
+    ports::BlogRepository,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn repository_errors_are_typed(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+
+    let req = CreateUserRequest::new(
+        "alice".to_string(),
+        None,
+        None,
+        common::PASSWORD.to_string(),
+        false,
+        3600,
+        Role::Author,
+    )
+    .unwrap();
+    let res = BlogRepository::create_user(&pg, &req).await;
+    assert!(matches!(res, Err(Error::Conflict(_))));
+
+    let req = GetUserRequest::new("nobody".to_string()).unwrap();
+    let res = BlogRepository::get_user(&pg, &req).await;
+    assert!(matches!(res, Err(Error::NotFound(_))));
+
+    let req = UpdatePostRequest::new(
+        "missing".to_string(),
+        "Title".to_string(),
+        "text".to_string(),
+        "alice".to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+    let res = BlogRepository::update_post(&pg, &req).await;
+    assert!(matches!(res, Err(Error::NotFound(_))));
+}
+
+#[sqlx::test]
+async fn duplicate_usernames_are_a_conflict(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let app = common::spawn_app(common::service(pg), common::config()).await;
+
+    let res = reqwest::Client::new()
+        .post(format!("{app}/api/auth/register"))
+        .json(&json!({
+            "username": "alice",
+            "password": common::PASSWORD,
+            "email": "alice2@example.com",
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+}
+
+#[sqlx::test]
+async fn errors_carry_a_code_request_id_and_field_details(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let app = common::spawn_app(common::service(pg), common::config()).await;
+    let client = reqwest::Client::new();
+    let token = common::login(&client, &app, "alice").await;
+
+    let res = client
+        .post(format!("{app}/api/posts"))
+        .bearer_auth(&token)
+        .header("x-request-id", "req-1")
+        .json(&json!({ "title": "x".repeat(51), "content": "text" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(res.headers()["x-request-id"], "req-1");
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["data"]["code"], "validation_failed");
+    assert_eq!(body["data"]["request_id"], "req-1");
+    assert_eq!(
+        body["data"]["fields"]["title"],
+        json!([{ "rule": "length", "params": { "min": 1, "max": 50 } }])
+    );
+    assert!(body["data"]["fields"].get("content").is_none());
+
+    let res = client
+        .get(format!("{app}/api/posts/missing"))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    let request_id = res.headers()["x-request-id"].to_str().unwrap().to_string();
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["data"]["code"], "not_found");
+    assert_eq!(body["data"]["request_id"], request_id.as_str());
+    assert!(body["data"].get("fields").is_none());
+}
+
+#[sqlx::test]
+async fn problem_json_is_served_when_preferred(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let app = common::spawn_app(common::service(pg), common::config()).await;
+    let client = reqwest::Client::new();
+    let token = common::login(&client, &app, "alice").await;
+    let get = |path: &str, accept: &str| {
+        client
+            .get(format!("{app}{path}"))
+            .bearer_auth(&token)
+            .header("accept", accept)
+            .send()
+    };
+
+    let res = get("/api/posts/missing", "application/problem+json")
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    assert_eq!(res.headers()["content-type"], "application/problem+json");
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["type"], "urn:blog-rs:problem:not_found");
+    assert_eq!(body["title"], "Not Found");
+    assert_eq!(body["status"], 404);
+    assert_eq!(body["instance"], "/api/posts/missing");
+    assert_eq!(body["code"], "not_found");
+    assert!(body["detail"].is_string());
+
+    for accept in [
+        "application/json",
+        "*/*",
+        "application/json, application/problem+json;q=0.5",
+    ] {
+        let res = get("/api/posts/missing", accept).await.unwrap();
+        assert_eq!(res.headers()["content-type"], "application/json");
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["status_code"], 404);
+        assert_eq!(body["data"]["code"], "not_found");
+    }
+
+    // Successful responses keep the regular envelope.
+    let res = get("/api/tags", "application/problem+json").await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], "application/json");
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use blog_rs::domain::blog::{
+    error::Error,
+    models::{
+        auth::{LoginAttempt, LoginPolicy, LoginResponse},
+        roles::Role,
+    },
+    ports::BlogService,
+};
+use sqlx::PgPool;
+
+const WRONG_PASSWORD: &str = "wrong-password";
+const IP: &str = "203.0.113.7";
+
+/// Waits 60 seconds after the first failure, doubling after each further one.
+fn policy() -> LoginPolicy {
+    let mut policy = common::login_policy(false);
+    policy.throttle.backoff_base = 60;
+    policy.throttle.backoff_max = 3600;
+    policy
+}
+
+async fn login<BS: BlogService>(
+    service: &BS,
+    username: &str,
+    password: &str,
+    ip: Option<&str>,
+) -> Result<LoginResponse, Error> {
+    let mut req = common::login_request(username, password, policy());
+    req.ip = ip.map(str::to_string);
+    service.login(&req).await
+}
+
+/// Moves the last failure of `key` into the past, as if time went by.
+async fn rewind(pool: &PgPool, key: &str, seconds: i32) {
+    sqlx::query(
+        r#"
+        UPDATE login_attempts
+        SET last_failed_at = last_failed_at - make_interval(secs => $2)
+        WHERE key = $1
+        "#,
+    )
+    .bind(key)
+    .bind(seconds)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+fn is_throttled(res: &Result<LoginResponse, Error>) -> bool {
+    matches!(res, Err(Error::TooManyRequests(_)))
+}
+
+fn is_rejected(res: &Result<LoginResponse, Error>) -> bool {
+    matches!(res, Err(Error::Unauthorized(_)))
+}
+
+#[sqlx::test]
+async fn backoff_grows_with_each_failure_for_a_username(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+    let key = LoginAttempt::username_key("alice");
+
+    assert!(is_rejected(
+        &login(&service, "alice", WRONG_PASSWORD, None).await
+    ));
+    // Even the right password waits for the backoff.
+    assert!(is_throttled(
+        &login(&service, "alice", common::PASSWORD, None).await
+    ));
+    rewind(&pool, &key, 61).await;
+    assert!(is_rejected(
+        &login(&service, "alice", WRONG_PASSWORD, None).await
+    ));
+    rewind(&pool, &key, 61).await;
+    assert!(is_throttled(
+        &login(&service, "alice", WRONG_PASSWORD, None).await
+    ));
+    rewind(&pool, &key, 60).await;
+    assert!(is_rejected(
+        &login(&service, "alice", WRONG_PASSWORD, None).await
+    ));
+}
+
+#[sqlx::test]
+async fn backoff_grows_for_an_ip_across_usernames(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    for username in ["alice", "bob", "carol"] {
+        common::create_user(&pg, username, Role::Author).await;
+    }
+    let service = common::service(pg);
+    let key = LoginAttempt::ip_key(IP);
+
+    assert!(is_rejected(
+        &login(&service, "alice", WRONG_PASSWORD, Some(IP)).await
+    ));
+    assert!(is_throttled(
+        &login(&service, "bob", WRONG_PASSWORD, Some(IP)).await
+    ));
+    rewind(&pool, &key, 61).await;
+    assert!(is_rejected(
+        &login(&service, "bob", WRONG_PASSWORD, Some(IP)).await
+    ));
+    rewind(&pool, &key, 61).await;
+    assert!(is_throttled(
+        &login(&service, "carol", common::PASSWORD, Some(IP)).await
+    ));
+    // Other clients are not slowed down.
+    assert!(
+        login(&service, "carol", common::PASSWORD, Some("198.51.100.1"))
+            .await
+            .is_ok()
+    );
+    rewind(&pool, &key, 60).await;
+    assert!(login(&service, "carol", common::PASSWORD, Some(IP))
+        .await
+        .is_ok());
+}
+
+#[sqlx::test]
+async fn successful_logins_reset_the_username_backoff(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+    let key = LoginAttempt::username_key("alice");
+
+    for _ in 0..2 {
+        assert!(is_rejected(
+            &login(&service, "alice", WRONG_PASSWORD, None).await
+        ));
+        rewind(&pool, &key, 121).await;
+    }
+    assert!(login(&service, "alice", common::PASSWORD, None)
+        .await
+        .is_ok());
+
+    // Back to the first step: a 60 second wait instead of 240.
+    assert!(is_rejected(
+        &login(&service, "alice", WRONG_PASSWORD, None).await
+    ));
+    assert!(is_throttled(
+        &login(&service, "alice", WRONG_PASSWORD, None).await
+    ));
+    rewind(&pool, &key, 61).await;
+    assert!(is_rejected(
+        &login(&service, "alice", WRONG_PASSWORD, None).await
+    ));
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use blog_rs::domain::blog::{
+    error::Error,
+    models::{
+        auth::{LoginPolicy, LoginResponse},
+        oidc::{OidcCallbackRequest, StartOidcLoginRequest},
+        roles::Role,
+        two_factor::VerifyTwoFactorRequest,
+    },
+    ports::BlogService,
+};
+use sqlx::PgPool;
+
+/// Runs the authorization code flow against the test provider.
+async fn oidc_login<BS: BlogService>(
+    service: &BS,
+    policy: LoginPolicy,
+) -> Result<LoginResponse, Error> {
+    let url = service
+        .start_oidc_login(&StartOidcLoginRequest::new(600).unwrap())
+        .await
+        .unwrap();
+    let (_, state) = url.split_once("state=").unwrap();
+    let req = OidcCallbackRequest::new(
+        "code".to_string(),
+        state.to_string(),
+        true,
+        Role::Author,
+        common::jwt(),
+        900,
+        3600,
+        policy,
+    )
+    .unwrap();
+    service.finish_oidc_login(&req).await
+}
+
+async fn identity_count(pool: &PgPool) -> i64 {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM user_identities")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    count
+}
+
+#[sqlx::test]
+async fn provisions_a_user_for_a_new_identity(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    let provider = common::TestIdentityProvider::new("sub-1", "carol@example.com", true);
+    let service = common::oidc_service(pg, provider);
+
+    let res = oidc_login(&service, common::login_policy(true))
+        .await
+        .unwrap();
+    assert!(matches!(res, LoginResponse::Authenticated(_)));
+    assert_eq!(common::user_count(&pool, "carol").await, 1);
+    assert!(service
+        .check_permission("carol", "/api/posts", "POST")
+        .await
+        .unwrap());
+}
+
+#[sqlx::test]
+async fn provisioned_usernames_are_valid_usernames(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    let mut provider = common::TestIdentityProvider::new("sub-1", "carol@example.com", true);
+    provider.0.preferred_username = Some("Carol:Smith*".to_string());
+    let service = common::oidc_service(pg, provider);
+
+    oidc_login(&service, common::login_policy(true))
+        .await
+        .unwrap();
+    assert_eq!(common::user_count(&pool, "CarolSmith").await, 1);
+}
+
+#[sqlx::test]
+async fn logs_in_the_linked_user(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    common::verify_email(&pool, "alice").await;
+    let provider = common::TestIdentityProvider::new("sub-1", "alice@example.com", true);
+    let service = common::oidc_service(pg, provider);
+
+    // The first login links the identity by email, later ones find the link.
+    for _ in 0..2 {
+        let res = oidc_login(&service, common::login_policy(true))
+            .await
+            .unwrap();
+        assert!(matches!(res, LoginResponse::Authenticated(_)));
+    }
+    assert_eq!(identity_count(&pool).await, 1);
+    assert_eq!(common::user_count(&pool, "alice1").await, 0);
+}
+
+#[sqlx::test]
+async fn asks_for_the_second_factor(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    let user = common::create_user(&pg, "alice", Role::Author).await;
+    common::verify_email(&pool, "alice").await;
+    let provider = common::TestIdentityProvider::new("sub-1", "alice@example.com", true);
+    let service = common::oidc_service(pg, provider);
+    let recovery_codes = common::enable_two_factor(&service, &user).await;
+
+    let res = oidc_login(&service, common::login_policy(false))
+        .await
+        .unwrap();
+    let LoginResponse::TwoFactorRequired {
+        challenge_token, ..
+    } = res
+    else {
+        panic!("expected a two-factor challenge");
+    };
+    let req = VerifyTwoFactorRequest::new(
+        challenge_token,
+        recovery_codes[0].clone(),
+        common::jwt(),
+        900,
+        3600,
+        common::login_policy(false),
+    )
+    .unwrap();
+    assert!(service.verify_two_factor(&req).await.is_ok());
+}
+
+#[sqlx::test]
+async fn refuses_unverified_accounts(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    let provider = common::TestIdentityProvider::new("sub-1", "carol@example.com", false);
+    let service = common::oidc_service(pg, provider);
+
+    let res = oidc_login(&service, common::login_policy(true)).await;
+    assert!(matches!(res, Err(Error::Forbidden(_))));
+    assert_eq!(common::user_count(&pool, "carol").await, 0);
+
+    let res = oidc_login(&service, common::login_policy(false)).await;
+    assert!(matches!(res, Ok(LoginResponse::Authenticated(_))));
+}
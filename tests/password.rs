@@ -0,0 +1,47 @@
+mod common;
+
+use blog_rs::domain::blog::{
+    models::{auth::ForgotPasswordRequest, roles::Role},
+    ports::BlogService,
+};
+use sqlx::PgPool;
+use std::time::Duration;
+
+#[sqlx::test]
+async fn forgot_password_mails_a_reset_token(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let mailer = common::TestMailer::default();
+    let service = common::service_with_mailer(pg, mailer.clone());
+
+    let req = ForgotPasswordRequest::new("alice@example.com".into(), 3600).unwrap();
+    service.forgot_password(&req).await.unwrap();
+    let sent = mailer.wait_for(1).await;
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].to, "alice@example.com");
+}
+
+#[sqlx::test]
+async fn forgot_password_hides_mail_failures(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service_with_mailer(pg, common::TestMailer::failing());
+
+    for email in ["alice@example.com", "nobody@example.com"] {
+        let req = ForgotPasswordRequest::new(email.into(), 3600).unwrap();
+        assert!(service.forgot_password(&req).await.is_ok());
+    }
+}
+
+#[sqlx::test]
+async fn forgot_password_does_not_wait_for_the_mailer(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service_with_mailer(pg, common::TestMailer::hanging());
+
+    for email in ["alice@example.com", "nobody@example.com"] {
+        let req = ForgotPasswordRequest::new(email.into(), 3600).unwrap();
+        let res = tokio::time::timeout(Duration::from_secs(5), service.forgot_password(&req)).await;
+        assert!(res.expect("forgot_password waited for the mailer").is_ok());
+    }
+}
@@ -0,0 +1,310 @@
+mod common;
+
+use blog_rs::{
+    domain::blog::{
+        error::Error,
+        models::{
+            posts::{
+                BatchDeletePostRequest, ChangePostStatusRequest, CreatePostRequest,
+                DeletePostRequest, GetPostBySlugRequest, GetPostBySlugResponse, GetPostRequest,
+                ListPostRequest, ListTrashedPostRequest, Post, PostStatus, PurgeTrashRequest,
+                TrashedPostRequest,
+            },
+            roles::Role,
+        },
+        ports::BlogRepository,
+    },
+    outbound::db::postgres::Pg,
+};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn other_users_cannot_read_a_draft_by_slug(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    common::create_user(&pg, "bob", Role::Author).await;
+    let post = common::create_post(&pg, "alice", "Secret plans", "draft").await;
+    assert_eq!(post.status, PostStatus::Draft);
+
+    let req = GetPostBySlugRequest::new("alice".into(), post.slug.clone(), "bob".into()).unwrap();
+    let res = BlogRepository::get_post_by_slug(&pg, &req).await;
+    assert!(matches!(res, Err(Error::NotFound(_))));
+
+    let req = GetPostBySlugRequest::new("alice".into(), post.slug.clone(), "alice".into()).unwrap();
+    let res = BlogRepository::get_post_by_slug(&pg, &req).await.unwrap();
+    assert!(matches!(res, GetPostBySlugResponse::Found(found) if found.id == post.id));
+}
+
+#[sqlx::test]
+async fn editors_can_read_drafts_by_slug(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    common::create_user(&pg, "erin", Role::Editor).await;
+    let post = common::create_post(&pg, "alice", "Secret plans", "draft").await;
+
+    let req = GetPostBySlugRequest::new("alice".into(), post.slug.clone(), "erin".into()).unwrap();
+    let res = BlogRepository::get_post_by_slug(&pg, &req).await.unwrap();
+    assert!(matches!(res, GetPostBySlugResponse::Found(found) if found.id == post.id));
+}
+
+#[sqlx::test]
+async fn other_users_read_published_posts_by_slug(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    common::create_user(&pg, "bob", Role::Author).await;
+    let post = common::create_post(&pg, "alice", "Release notes", "out now").await;
+    let req = ChangePostStatusRequest::new(post.id.clone(), "alice".into(), PostStatus::Published)
+        .unwrap();
+    BlogRepository::change_post_status(&pg, &req).await.unwrap();
+
+    let req = GetPostBySlugRequest::new("alice".into(), post.slug.clone(), "bob".into()).unwrap();
+    let res = BlogRepository::get_post_by_slug(&pg, &req).await.unwrap();
+    assert!(matches!(res, GetPostBySlugResponse::Found(found) if found.id == post.id));
+}
+
+async fn change_status(pg: &Pg, id: &str, status: PostStatus) -> Result<PostStatus, Error> {
+    let req = ChangePostStatusRequest::new(id.to_string(), "alice".into(), status).unwrap();
+    let post = BlogRepository::change_post_status(pg, &req).await?;
+    Ok(post.status)
+}
+
+#[sqlx::test]
+async fn posts_move_through_the_status_lifecycle(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let post = common::create_post(&pg, "alice", "Lifecycle", "text").await;
+
+    let res = change_status(&pg, &post.id, PostStatus::Archived).await;
+    assert!(matches!(res, Err(Error::Conflict(_))));
+    for status in [
+        PostStatus::Published,
+        PostStatus::Archived,
+        PostStatus::Draft,
+        PostStatus::Published,
+        PostStatus::Draft,
+    ] {
+        assert_eq!(change_status(&pg, &post.id, status).await.unwrap(), status);
+    }
+    let res = change_status(&pg, &post.id, PostStatus::Draft).await;
+    assert!(matches!(res, Err(Error::Conflict(_))));
+}
+
+/// Schedules a post and then moves its publish time into the past, which
+/// requests refuse to do.
+async fn due_post(pg: &Pg, pool: &PgPool, title: &str) -> Post {
+    let publish_at = Some(Utc::now() + Duration::hours(1));
+    let req = CreatePostRequest::new(
+        title.to_string(),
+        "scheduled".to_string(),
+        "alice".to_string(),
+        publish_at,
+        vec![],
+    )
+    .unwrap();
+    let post = BlogRepository::create_post(pg, &req).await.unwrap();
+    sqlx::query("UPDATE posts SET publish_at = NOW() - INTERVAL '1 minute' WHERE id = $1")
+        .bind(&post.id)
+        .execute(pool)
+        .await
+        .unwrap();
+    post
+}
+
+async fn post_status(pg: &Pg, id: &str) -> PostStatus {
+    let req = GetPostRequest::new(id.to_string(), "alice".into()).unwrap();
+    BlogRepository::get_post(pg, &req).await.unwrap().status
+}
+
+#[test]
+fn posts_cannot_be_scheduled_in_the_past() {
+    let req = CreatePostRequest::new(
+        "Late".to_string(),
+        "text".to_string(),
+        "alice".to_string(),
+        Some(Utc::now() - Duration::minutes(1)),
+        vec![],
+    );
+    assert!(matches!(req, Err(Error::ValidationError(_))));
+}
+
+#[sqlx::test]
+async fn due_posts_are_published_once(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let post = due_post(&pg, &pool, "Scheduled").await;
+    assert_eq!(post.status, PostStatus::Draft);
+
+    assert_eq!(BlogRepository::publish_due_posts(&pg).await.unwrap(), 1);
+    assert_eq!(post_status(&pg, &post.id).await, PostStatus::Published);
+    assert_eq!(BlogRepository::publish_due_posts(&pg).await.unwrap(), 0);
+}
+
+#[sqlx::test]
+async fn posts_claimed_by_another_scheduler_are_skipped(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let post = due_post(&pg, &pool, "Scheduled").await;
+
+    // Another instance holds the row lock while it publishes the post.
+    let mut other = pool.begin().await.unwrap();
+    sqlx::query("SELECT id FROM posts WHERE id = $1 FOR UPDATE")
+        .bind(&post.id)
+        .execute(&mut *other)
+        .await
+        .unwrap();
+    assert_eq!(BlogRepository::publish_due_posts(&pg).await.unwrap(), 0);
+    other.rollback().await.unwrap();
+
+    assert_eq!(BlogRepository::publish_due_posts(&pg).await.unwrap(), 1);
+    assert_eq!(post_status(&pg, &post.id).await, PostStatus::Published);
+}
+
+async fn trashed_posts(pg: &Pg, username: &str, author: &str) -> Result<Vec<String>, Error> {
+    let req =
+        ListTrashedPostRequest::new(0, 50, username.into(), Some(author.to_string())).unwrap();
+    let res = BlogRepository::list_trashed_posts(pg, &req).await?;
+    Ok(res.posts.into_iter().map(|post| post.id).collect())
+}
+
+#[sqlx::test]
+async fn editors_manage_the_trash_of_other_authors(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    common::create_user(&pg, "bob", Role::Author).await;
+    common::create_user(&pg, "erin", Role::Editor).await;
+    let first = common::create_post(&pg, "alice", "First", "one").await;
+    let second = common::create_post(&pg, "alice", "Second", "two").await;
+
+    // Batch deletes by other authors leave the posts alone.
+    let ids = vec![first.id.clone(), second.id.clone()];
+    let req = BatchDeletePostRequest::new(ids.clone(), "bob".into()).unwrap();
+    BlogRepository::batch_delete_post(&pg, &req).await.unwrap();
+    assert!(trashed_posts(&pg, "alice", "alice")
+        .await
+        .unwrap()
+        .is_empty());
+
+    let req = BatchDeletePostRequest::new(ids, "erin".into()).unwrap();
+    BlogRepository::batch_delete_post(&pg, &req).await.unwrap();
+    assert_eq!(trashed_posts(&pg, "erin", "alice").await.unwrap().len(), 2);
+    assert!(matches!(
+        trashed_posts(&pg, "bob", "alice").await,
+        Err(Error::Forbidden(_))
+    ));
+
+    let req = TrashedPostRequest::new(first.id.clone(), "bob".into()).unwrap();
+    let res = BlogRepository::restore_trashed_post(&pg, &req).await;
+    assert!(matches!(res, Err(Error::NotFound(_))));
+    let res = BlogRepository::delete_trashed_post(&pg, &req).await;
+    assert!(matches!(res, Err(Error::NotFound(_))));
+
+    let req = TrashedPostRequest::new(first.id.clone(), "erin".into()).unwrap();
+    let restored = BlogRepository::restore_trashed_post(&pg, &req)
+        .await
+        .unwrap();
+    assert_eq!(restored.id, first.id);
+    let req = TrashedPostRequest::new(second.id.clone(), "erin".into()).unwrap();
+    BlogRepository::delete_trashed_post(&pg, &req)
+        .await
+        .unwrap();
+    assert!(trashed_posts(&pg, "alice", "alice")
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[sqlx::test]
+async fn authors_manage_their_own_trash(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let post = common::create_post(&pg, "alice", "First", "one").await;
+    let req = DeletePostRequest::new(post.id.clone(), "alice".into()).unwrap();
+    BlogRepository::delete_post(&pg, &req).await.unwrap();
+
+    let req = ListTrashedPostRequest::new(0, 50, "alice".into(), None).unwrap();
+    let res = BlogRepository::list_trashed_posts(&pg, &req).await.unwrap();
+    assert_eq!(res.total, 1);
+    let req = TrashedPostRequest::new(post.id.clone(), "alice".into()).unwrap();
+    BlogRepository::restore_trashed_post(&pg, &req)
+        .await
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn trashed_posts_are_purged_after_the_retention_period(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let kept = common::create_post(&pg, "alice", "Kept", "one").await;
+    let old = common::create_post(&pg, "alice", "Old", "two").await;
+    let recent = common::create_post(&pg, "alice", "Recent", "three").await;
+
+    // Restoring a post that is not in the trash fails.
+    let req = TrashedPostRequest::new(kept.id.clone(), "alice".into()).unwrap();
+    let res = BlogRepository::restore_trashed_post(&pg, &req).await;
+    assert!(matches!(res, Err(Error::NotFound(_))));
+
+    for post in [&old, &recent] {
+        let req = DeletePostRequest::new(post.id.clone(), "alice".into()).unwrap();
+        BlogRepository::delete_post(&pg, &req).await.unwrap();
+    }
+    let req = ListPostRequest::new(0, 50, "alice".into(), None).unwrap();
+    let res = BlogRepository::list_post(&pg, req).await.unwrap();
+    assert_eq!(res.total, 1);
+    assert_eq!(res.posts[0].id, kept.id);
+
+    sqlx::query("UPDATE posts SET deleted_at = NOW() - INTERVAL '31 days' WHERE id = $1")
+        .bind(&old.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let req = PurgeTrashRequest::new(30);
+    assert_eq!(BlogRepository::purge_trash(&pg, &req).await.unwrap(), 1);
+    assert_eq!(BlogRepository::purge_trash(&pg, &req).await.unwrap(), 0);
+
+    let req = TrashedPostRequest::new(old.id.clone(), "alice".into()).unwrap();
+    let res = BlogRepository::restore_trashed_post(&pg, &req).await;
+    assert!(matches!(res, Err(Error::NotFound(_))));
+    let req = TrashedPostRequest::new(recent.id.clone(), "alice".into()).unwrap();
+    let restored = BlogRepository::restore_trashed_post(&pg, &req)
+        .await
+        .unwrap();
+    assert!(restored.deleted_at.is_none());
+}
+
+#[sqlx::test]
+async fn posts_are_fetched_by_id_by_their_author_only(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    common::create_user(&pg, "bob", Role::Author).await;
+    let post = common::create_post(&pg, "alice", "Draft", "text").await;
+    let app = common::spawn_app(common::service(pg), common::config()).await;
+    let client = reqwest::Client::new();
+    let alice = common::login(&client, &app, "alice").await;
+    let bob = common::login(&client, &app, "bob").await;
+
+    let res: serde_json::Value = client
+        .get(format!("{app}/api/posts/{}", post.id))
+        .bearer_auth(&alice)
+        .send()
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(res["data"]["id"], post.id.as_str());
+    assert_eq!(res["data"]["slug"], "draft");
+    assert_eq!(res["data"]["status"], "draft");
+
+    for (token, id) in [(&bob, post.id.as_str()), (&alice, "missing")] {
+        let res = client
+            .get(format!("{app}/api/posts/{id}"))
+            .bearer_auth(token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use blog_rs::domain::blog::{
+    models::{
+        posts::{ChangePostStatusRequest, PostStatus},
+        roles::Role,
+    },
+    ports::BlogRepository,
+};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn anonymous_readers_only_see_published_posts(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let published = common::create_post(&pg, "alice", "Released", "out now").await;
+    let req =
+        ChangePostStatusRequest::new(published.id.clone(), "alice".into(), PostStatus::Published)
+            .unwrap();
+    BlogRepository::change_post_status(&pg, &req).await.unwrap();
+    let draft = common::create_post(&pg, "alice", "Secret plans", "draft").await;
+    let app = common::spawn_app(common::service(pg), common::config()).await;
+    let client = reqwest::Client::new();
+
+    let res: serde_json::Value = client
+        .get(format!(
+            "{app}/api/public/users/alice/posts?offset=0&limit=10"
+        ))
+        .send()
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(res["data"]["total"], 1);
+    assert_eq!(res["data"]["posts"][0]["id"], published.id.as_str());
+
+    for (post, status) in [
+        (&published, StatusCode::OK),
+        (&draft, StatusCode::NOT_FOUND),
+    ] {
+        let res = client
+            .get(format!("{app}/api/public/posts/{}", post.id))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), status);
+        let res = client
+            .get(format!(
+                "{app}/api/public/posts/by-slug/alice/{}",
+                post.slug
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), status);
+    }
+
+    // The same routes behind authentication are not reachable anonymously.
+    let res = client
+        .get(format!("{app}/api/posts/{}", published.id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use blog_rs::{
+    domain::blog::{
+        error::Error,
+        models::{
+            posts::UpdatePostRequest,
+            revisions::{
+                ListPostRevisionsRequest, PostRevision, PostRevisionDiff,
+                RestorePostRevisionRequest,
+            },
+            roles::Role,
+        },
+        ports::BlogRepository,
+    },
+    outbound::db::postgres::Pg,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+
+fn revision(revision: i32, title: &str, content: &str) -> PostRevision {
+    PostRevision {
+        id: revision.to_string(),
+        post_id: "post".to_string(),
+        revision,
+        title: title.to_string(),
+        content: content.to_string(),
+        created_at: Utc::now(),
+    }
+}
+
+async fn update_post(pg: &Pg, id: &str, title: &str, content: &str) {
+    let req = UpdatePostRequest::new(
+        id.to_string(),
+        title.to_string(),
+        content.to_string(),
+        "alice".to_string(),
+        None,
+        None,
+    )
+    .unwrap();
+    BlogRepository::update_post(pg, &req).await.unwrap();
+}
+
+async fn revisions(pg: &Pg, id: &str, username: &str) -> Result<Vec<(i32, String)>, Error> {
+    let req = ListPostRevisionsRequest::new(id.to_string(), username.to_string()).unwrap();
+    let mut res: Vec<(i32, String)> = BlogRepository::list_post_revisions(pg, &req)
+        .await?
+        .into_iter()
+        .map(|rev| (rev.revision, rev.title))
+        .collect();
+    res.sort();
+    Ok(res)
+}
+
+#[test]
+fn diffs_show_content_changes() {
+    let diff = PostRevisionDiff::new(
+        revision(1, "Notes", "one\ntwo\n"),
+        revision(2, "Notes", "one\nthree\n"),
+    );
+    assert!(diff.diff.contains("--- revision 1\n+++ revision 2\n"));
+    assert!(diff.diff.contains("-two\n+three\n"));
+    assert!(!diff.diff.contains("-title"));
+}
+
+#[test]
+fn diffs_show_title_changes() {
+    let diff = PostRevisionDiff::new(
+        revision(1, "Draft notes", "same\n"),
+        revision(2, "Final notes", "same\n"),
+    );
+    assert!(diff
+        .diff
+        .contains("-title: Draft notes\n+title: Final notes\n"));
+}
+
+#[sqlx::test]
+async fn every_update_stores_a_revision(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    common::create_user(&pg, "bob", Role::Author).await;
+    let post = common::create_post(&pg, "alice", "First", "one").await;
+    update_post(&pg, &post.id, "Second", "two").await;
+
+    assert_eq!(
+        revisions(&pg, &post.id, "alice").await.unwrap(),
+        [(1, "First".to_string()), (2, "Second".to_string())]
+    );
+    assert!(matches!(
+        revisions(&pg, &post.id, "bob").await,
+        Err(Error::NotFound(_))
+    ));
+}
+
+#[sqlx::test]
+async fn restoring_a_revision_adds_a_new_one(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let post = common::create_post(&pg, "alice", "First", "one").await;
+    update_post(&pg, &post.id, "Second", "two").await;
+
+    let req = RestorePostRevisionRequest::new(post.id.clone(), "alice".into(), 1).unwrap();
+    let restored = BlogRepository::restore_post_revision(&pg, &req)
+        .await
+        .unwrap();
+    assert_eq!(restored.title, "First");
+    assert_eq!(restored.content, "one");
+    assert_eq!(
+        revisions(&pg, &post.id, "alice").await.unwrap(),
+        [
+            (1, "First".to_string()),
+            (2, "Second".to_string()),
+            (3, "First".to_string())
+        ]
+    );
+
+    let req = RestorePostRevisionRequest::new(post.id.clone(), "alice".into(), 9).unwrap();
+    let res = BlogRepository::restore_post_revision(&pg, &req).await;
+    assert!(matches!(res, Err(Error::NotFound(_))));
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use blog_rs::domain::blog::{
+    error::Error,
+    models::{
+        policies::{
+            GetUserRolesRequest, PolicyRequest, RoleAssignmentRequest, RoleGroupingRequest,
+        },
+        roles::Role,
+    },
+    ports::BlogService,
+};
+use sqlx::PgPool;
+
+/// Whether `sub` may `act` on `obj`, asked of the running enforcer and of
+/// one freshly loaded from `casbin_rule`.
+async fn allowed<BS: BlogService>(
+    service: &BS,
+    pool: &PgPool,
+    sub: &str,
+    obj: &str,
+    act: &str,
+) -> (bool, bool) {
+    let reloaded = common::service(common::pg(pool.clone()).await);
+    (
+        service.check_permission(sub, obj, act).await.unwrap(),
+        reloaded.check_permission(sub, obj, act).await.unwrap(),
+    )
+}
+
+#[sqlx::test]
+async fn roles_are_assigned_and_revoked(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    common::create_user(&pg, "alice", Role::Reader).await;
+    common::create_user(&pg, "bob", Role::Author).await;
+    let service = common::service(pg);
+    let admin = || RoleAssignmentRequest::new("alice".into(), "admin").unwrap();
+
+    service.assign_role(&admin()).await.unwrap();
+    assert_eq!(
+        allowed(&service, &pool, "alice", "/api/users/bob", "GET").await,
+        (true, true)
+    );
+    let roles = service
+        .get_user_roles(&GetUserRolesRequest::new("alice".into()).unwrap())
+        .await
+        .unwrap();
+    assert!(roles.roles.contains(&Role::Admin.subject()));
+
+    service.revoke_role(&admin()).await.unwrap();
+    assert_eq!(
+        allowed(&service, &pool, "alice", "/api/users/bob", "GET").await,
+        (false, false)
+    );
+    // The default role is untouched.
+    assert_eq!(
+        allowed(&service, &pool, "alice", "/api/posts", "GET").await,
+        (true, true)
+    );
+    let res = service.revoke_role(&admin()).await;
+    assert!(matches!(res, Err(Error::NotFound(_))));
+    let res = service
+        .revoke_role(&RoleAssignmentRequest::new("nobody".into(), "admin").unwrap())
+        .await;
+    assert!(matches!(res, Err(Error::NotFound(_))));
+}
+
+#[sqlx::test]
+async fn failed_revokes_keep_the_role(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    common::create_user(&pg, "alice", Role::Admin).await;
+    let service = common::service(pg);
+    sqlx::raw_sql(
+        r#"
+        CREATE FUNCTION fail_policy_delete() RETURNS trigger AS $$
+        BEGIN
+            RAISE EXCEPTION 'injected policy delete failure';
+        END
+        $$ LANGUAGE plpgsql;
+        CREATE TRIGGER fail_policy_delete BEFORE DELETE ON casbin_rule
+        FOR EACH ROW EXECUTE FUNCTION fail_policy_delete();
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let req = RoleAssignmentRequest::new("alice".into(), "admin").unwrap();
+    assert!(service.revoke_role(&req).await.is_err());
+    assert_eq!(
+        allowed(&service, &pool, "alice", "/api/users/bob", "GET").await,
+        (true, true)
+    );
+}
+
+#[sqlx::test]
+async fn policies_are_created_and_deleted(pool: PgPool) {
+    let service = common::service(common::pg(pool.clone()).await);
+    let policy =
+        || PolicyRequest::new("role:reader".into(), "/api/reports/*".into(), "GET".into()).unwrap();
+
+    service.create_policy(&policy()).await.unwrap();
+    let res = service.create_policy(&policy()).await;
+    assert!(matches!(res, Err(Error::Conflict(_))));
+    assert_eq!(
+        allowed(&service, &pool, "role:reader", "/api/reports/1", "GET").await,
+        (true, true)
+    );
+
+    service.delete_policy(&policy()).await.unwrap();
+    let res = service.delete_policy(&policy()).await;
+    assert!(matches!(res, Err(Error::NotFound(_))));
+    assert_eq!(
+        allowed(&service, &pool, "role:reader", "/api/reports/1", "GET").await,
+        (false, false)
+    );
+}
+
+#[sqlx::test]
+async fn role_groupings_are_created_and_deleted(pool: PgPool) {
+    let service = common::service(common::pg(pool.clone()).await);
+    let grouping = || RoleGroupingRequest::new("role:auditor".into(), "role:admin".into()).unwrap();
+
+    service.create_role_grouping(&grouping()).await.unwrap();
+    let res = service.create_role_grouping(&grouping()).await;
+    assert!(matches!(res, Err(Error::Conflict(_))));
+    assert_eq!(
+        allowed(
+            &service,
+            &pool,
+            "role:auditor",
+            "/api/admin/policies",
+            "GET"
+        )
+        .await,
+        (true, true)
+    );
+
+    service.delete_role_grouping(&grouping()).await.unwrap();
+    let res = service.delete_role_grouping(&grouping()).await;
+    assert!(matches!(res, Err(Error::NotFound(_))));
+    assert_eq!(
+        allowed(
+            &service,
+            &pool,
+            "role:auditor",
+            "/api/admin/policies",
+            "GET"
+        )
+        .await,
+        (false, false)
+    );
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use blog_rs::domain::blog::{
+    models::{
+        posts::{ChangePostStatusRequest, PostStatus},
+        roles::Role,
+        search::SearchPostRequest,
+    },
+    ports::BlogRepository,
+};
+use sqlx::PgPool;
+
+#[sqlx::test]
+async fn highlights_escape_post_markup(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let post = common::create_post(
+        &pg,
+        "alice",
+        "<script>alert(1)</script> release",
+        "release <img src=x onerror=alert(1)> notes",
+    )
+    .await;
+    let req = ChangePostStatusRequest::new(post.id, "alice".into(), PostStatus::Published).unwrap();
+    BlogRepository::change_post_status(&pg, &req).await.unwrap();
+
+    let req = SearchPostRequest::new("release".into(), 0, 10, "bob".into()).unwrap();
+    let res = BlogRepository::search_post(&pg, &req).await.unwrap();
+    assert_eq!(res.results.len(), 1);
+    let result = &res.results[0];
+    assert!(!result.title_highlight.contains("<script>"));
+    assert!(result.title_highlight.contains("&lt;script&gt;"));
+    assert!(result.title_highlight.contains("<mark>release</mark>"));
+    assert!(!result.snippet.contains("<img"));
+    assert!(result.snippet.contains("&lt;img"));
+    assert!(result.snippet.contains("<mark>release</mark>"));
+}
+
+#[sqlx::test]
+async fn highlights_keep_entities_intact(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    common::create_post(&pg, "alice", "a < b", "lt means < and gt means > here").await;
+
+    for (q, highlight) in [("lt", "<mark>lt</mark>"), ("gt", "<mark>gt</mark>")] {
+        let req = SearchPostRequest::new(q.into(), 0, 10, "alice".into()).unwrap();
+        let res = BlogRepository::search_post(&pg, &req).await.unwrap();
+        assert_eq!(res.results.len(), 1);
+        let result = &res.results[0];
+        assert_eq!(result.title_highlight, "a &lt; b");
+        assert!(result.snippet.contains(highlight), "{}", result.snippet);
+        assert!(result.snippet.contains("&lt; and"), "{}", result.snippet);
+        assert!(result.snippet.contains("&gt;"), "{}", result.snippet);
+    }
+}
+
+#[sqlx::test]
+async fn query_syntax_in_the_input_is_not_interpreted(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    common::create_post(&pg, "alice", "It's a b", "c d e").await;
+
+    let q = r#"it's "a & b" !c | (d) <-> e:*"#;
+    let req = SearchPostRequest::new(q.into(), 0, 10, "alice".into()).unwrap();
+    let res = BlogRepository::search_post(&pg, &req).await.unwrap();
+    assert_eq!(res.total, 1);
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use blog_rs::{
+    domain::blog::{
+        error::Error,
+        models::{
+            posts::{
+                ChangePostStatusRequest, CreatePostRequest, ListPostRequest, Post, PostStatus,
+                TagMatch,
+            },
+            roles::Role,
+        },
+        ports::BlogRepository,
+    },
+    outbound::db::postgres::Pg,
+};
+use sqlx::PgPool;
+
+fn create_post_request(title: &str, tags: &[&str]) -> Result<CreatePostRequest, Error> {
+    CreatePostRequest::new(
+        title.to_string(),
+        "text".to_string(),
+        "alice".to_string(),
+        None,
+        tags.iter().map(|tag| tag.to_string()).collect(),
+    )
+}
+
+async fn create_post(pg: &Pg, title: &str, tags: &[&str], status: PostStatus) -> Post {
+    let req = create_post_request(title, tags).unwrap();
+    let post = BlogRepository::create_post(pg, &req).await.unwrap();
+    if status == PostStatus::Draft {
+        return post;
+    }
+    let req = ChangePostStatusRequest::new(post.id, "alice".into(), status).unwrap();
+    BlogRepository::change_post_status(pg, &req).await.unwrap()
+}
+
+async fn list_titles(pg: &Pg, tags: &[&str], tag_match: TagMatch) -> Vec<String> {
+    let tags = tags.iter().map(|tag| tag.to_string()).collect();
+    let req = ListPostRequest::new(0, 50, "alice".into(), None)
+        .unwrap()
+        .with_tags(tags, tag_match)
+        .unwrap();
+    let res = BlogRepository::list_post(pg, req).await.unwrap();
+    let mut titles: Vec<String> = res.posts.into_iter().map(|post| post.title).collect();
+    titles.sort();
+    titles
+}
+
+#[test]
+fn tags_cannot_contain_commas() {
+    assert!(create_post_request("Tagged", &["rust", " Web "]).is_ok());
+    let res = create_post_request("Tagged", &["rust,web"]);
+    assert!(matches!(res, Err(Error::ValidationError(_))));
+}
+
+#[sqlx::test]
+async fn posts_are_filtered_by_any_or_all_tags(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    create_post(&pg, "Both", &["rust", "web"], PostStatus::Draft).await;
+    create_post(&pg, "Rust", &["Rust"], PostStatus::Draft).await;
+    create_post(&pg, "Untagged", &[], PostStatus::Draft).await;
+
+    assert_eq!(
+        list_titles(&pg, &["rust", "web"], TagMatch::Any).await,
+        ["Both", "Rust"]
+    );
+    assert_eq!(
+        list_titles(&pg, &["rust", "web"], TagMatch::All).await,
+        ["Both"]
+    );
+    assert!(list_titles(&pg, &["go"], TagMatch::Any).await.is_empty());
+}
+
+#[sqlx::test]
+async fn tag_listing_only_counts_published_posts(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    create_post(&pg, "Released", &["rust"], PostStatus::Published).await;
+    create_post(&pg, "Also released", &["rust"], PostStatus::Published).await;
+    create_post(&pg, "Plans", &["rust", "secret"], PostStatus::Draft).await;
+
+    let tags: Vec<(String, i64)> = BlogRepository::list_tags(&pg)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|tag| (tag.name, tag.post_count))
+        .collect();
+    assert_eq!(tags, [("rust".to_string(), 2)]);
+}
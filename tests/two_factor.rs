@@ -0,0 +1,159 @@
+mod common;
+
+use blog_rs::domain::blog::{
+    error::Error,
+    models::{
+        auth::LoginResponse,
+        roles::Role,
+        two_factor::{DisableTotpRequest, VerifyTwoFactorRequest},
+    },
+    ports::BlogService,
+};
+use sqlx::PgPool;
+
+const WRONG_CODE: &str = "000000";
+
+async fn challenge<BS: BlogService>(service: &BS) -> Result<String, Error> {
+    let req = common::login_request("alice", common::PASSWORD, common::login_policy(false));
+    match service.login(&req).await? {
+        LoginResponse::TwoFactorRequired {
+            challenge_token, ..
+        } => Ok(challenge_token),
+        LoginResponse::Authenticated(_) => panic!("expected a two-factor challenge"),
+    }
+}
+
+async fn verify<BS: BlogService>(
+    service: &BS,
+    challenge_token: &str,
+    code: &str,
+) -> Result<(), Error> {
+    let req = VerifyTwoFactorRequest::new(
+        challenge_token.to_string(),
+        code.to_string(),
+        common::jwt(),
+        900,
+        3600,
+        common::login_policy(false),
+    )
+    .unwrap();
+    service.verify_two_factor(&req).await.map(|_| ())
+}
+
+async fn login_failures(pool: &PgPool) -> Option<i32> {
+    let res: Option<(i32,)> =
+        sqlx::query_as("SELECT failures FROM login_attempts WHERE key = 'user:alice'")
+            .fetch_optional(pool)
+            .await
+            .unwrap();
+    res.map(|(failures,)| failures)
+}
+
+#[sqlx::test]
+async fn wrong_codes_lock_the_account_across_challenges(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    let user = common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+    common::enable_two_factor(&service, &user).await;
+    let max_failures = common::config().auth.login_throttle.max_failures;
+
+    // A fresh challenge per guess must not reset the count.
+    for _ in 0..max_failures {
+        let challenge_token = challenge(&service).await.unwrap();
+        let res = verify(&service, &challenge_token, WRONG_CODE).await;
+        assert!(matches!(res, Err(Error::Unauthorized(_))));
+    }
+    assert!(matches!(
+        challenge(&service).await,
+        Err(Error::TooManyRequests(_))
+    ));
+}
+
+#[sqlx::test]
+async fn locked_accounts_cannot_finish_a_pending_challenge(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    let user = common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+    let recovery_codes = common::enable_two_factor(&service, &user).await;
+    let max_failures = common::config().auth.login_throttle.max_failures;
+
+    let pending = challenge(&service).await.unwrap();
+    for _ in 0..max_failures {
+        let challenge_token = challenge(&service).await.unwrap();
+        verify(&service, &challenge_token, WRONG_CODE)
+            .await
+            .unwrap_err();
+    }
+    let res = verify(&service, &pending, &recovery_codes[0]).await;
+    assert!(matches!(res, Err(Error::TooManyRequests(_))));
+}
+
+#[sqlx::test]
+async fn failures_are_cleared_once_the_second_factor_is_verified(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    let user = common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+    let recovery_codes = common::enable_two_factor(&service, &user).await;
+
+    let challenge_token = challenge(&service).await.unwrap();
+    verify(&service, &challenge_token, WRONG_CODE)
+        .await
+        .unwrap_err();
+    // The right password alone does not clear the failed code.
+    let challenge_token = challenge(&service).await.unwrap();
+    assert_eq!(login_failures(&pool).await, Some(1));
+
+    verify(&service, &challenge_token, &recovery_codes[0])
+        .await
+        .unwrap();
+    assert_eq!(login_failures(&pool).await, None);
+}
+
+#[sqlx::test]
+async fn disabling_totp_is_throttled(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    let user = common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+    let recovery_codes = common::enable_two_factor(&service, &user).await;
+    let throttle = common::login_policy(false).throttle;
+    let disable =
+        |code: &str| DisableTotpRequest::new(user.id.clone(), code.to_string(), throttle).unwrap();
+
+    for _ in 0..throttle.max_failures {
+        let res = service.disable_totp(&disable(WRONG_CODE)).await;
+        assert!(matches!(res, Err(Error::Unauthorized(_))));
+    }
+    let res = service.disable_totp(&disable(&recovery_codes[0])).await;
+    assert!(matches!(res, Err(Error::TooManyRequests(_))));
+}
+
+#[sqlx::test]
+async fn totp_codes_are_accepted_only_once(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    let user = common::create_user(&pg, "alice", Role::Author).await;
+    let service = common::service(pg);
+    common::enable_two_factor(&service, &user).await;
+    // Confirming the enrollment used up the current code.
+    let (secret,): (String,) = sqlx::query_as(
+        "UPDATE users SET totp_last_step = NULL WHERE username = 'alice' RETURNING totp_secret",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let code = common::totp_code(&secret);
+
+    let first = challenge(&service).await.unwrap();
+    let second = challenge(&service).await.unwrap();
+    let (a, b) = tokio::join!(
+        verify(&service, &first, &code),
+        verify(&service, &second, &code)
+    );
+    assert_eq!([&a, &b].iter().filter(|res| res.is_ok()).count(), 1);
+    assert!([a, b]
+        .into_iter()
+        .any(|res| matches!(res, Err(Error::Unauthorized(_)))));
+
+    let third = challenge(&service).await.unwrap();
+    let res = verify(&service, &third, &code).await;
+    assert!(matches!(res, Err(Error::Unauthorized(_))));
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use blog_rs::{
+    domain::blog::{
+        error::Error,
+        models::{
+            roles::{casbin_path, user_object, user_subject, Role},
+            users::{CreateUserRequest, DeleteUserRequest},
+        },
+        ports::BlogRepository,
+    },
+    utils::password_hash::compute_password_hash,
+};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+fn create_user_request(username: &str) -> Result<CreateUserRequest, Error> {
+    CreateUserRequest::new(
+        username.to_string(),
+        None,
+        None,
+        common::PASSWORD.to_string(),
+        false,
+        3600,
+        Role::Author,
+    )
+}
+
+#[test]
+fn usernames_may_not_contain_casbin_syntax() {
+    for username in ["alice", "Alice", "ünïcode", "alice.smith", "bob 2", "100%"] {
+        assert!(create_user_request(username).is_ok(), "{username}");
+    }
+    for username in ["*", "a*", "role:admin", "a/b"] {
+        assert!(create_user_request(username).is_err(), "{username}");
+    }
+}
+
+#[test]
+fn usernames_are_escaped_in_casbin_rules() {
+    assert_eq!(user_subject("role:admin"), "role%3Aadmin");
+    assert_eq!(user_subject("100%"), "100%25");
+    assert_eq!(user_object("a*"), "/api/users/a%2A");
+    assert_eq!(user_object("ünï"), "/api/users/%C3%BCn%C3%AF");
+    assert_eq!(casbin_path("/api/users/a*"), "/api/users/a%2A");
+    assert_eq!(
+        casbin_path("/api/users/%c3%bcn%C3%AF"),
+        "/api/users/%C3%BCn%C3%AF"
+    );
+    assert_eq!(casbin_path("/api/users/a%2Fb"), "/api/users/a%2Fb");
+}
+
+async fn get_user(client: &reqwest::Client, app: &str, token: &str, username: &str) -> StatusCode {
+    client
+        .get(format!("{app}/api/users/{username}"))
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+#[sqlx::test]
+async fn users_with_any_allowed_name_reach_only_their_own_account(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    for username in ["Alice", "ünïcode", "bob"] {
+        let req = create_user_request(username).unwrap();
+        BlogRepository::create_user(&pg, &req).await.unwrap();
+    }
+    let app = common::spawn_app(common::service(pg), common::config()).await;
+    let client = reqwest::Client::new();
+
+    for username in ["Alice", "ünïcode"] {
+        let token = common::login(&client, &app, username).await;
+        assert_eq!(
+            get_user(&client, &app, &token, username).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            get_user(&client, &app, &token, "bob").await,
+            StatusCode::FORBIDDEN
+        );
+    }
+}
+
+/// Names from before the username check still get escaped rules.
+#[sqlx::test]
+async fn legacy_usernames_cannot_pose_as_patterns_or_roles(pool: PgPool) {
+    let pg = common::pg(pool).await;
+    common::create_user(&pg, "bob", Role::Author).await;
+    let hash = compute_password_hash(common::PASSWORD).unwrap();
+    for username in ["a*", "role:admin"] {
+        let mut tx = pg.pool.begin().await.unwrap();
+        pg.save_user(&mut tx, username, &None, &None, &hash)
+            .await
+            .unwrap();
+        pg.save_user_policy(&mut tx, username, Role::Author)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        pg.load_user_policy(username, Role::Author).await;
+    }
+    let app = common::spawn_app(common::service(pg), common::config()).await;
+    let client = reqwest::Client::new();
+
+    let token = common::login(&client, &app, "a*").await;
+    assert_eq!(get_user(&client, &app, &token, "a*").await, StatusCode::OK);
+    assert_eq!(
+        get_user(&client, &app, &token, "bob").await,
+        StatusCode::FORBIDDEN
+    );
+    let token = common::login(&client, &app, "role:admin").await;
+    assert_eq!(
+        get_user(&client, &app, &token, "bob").await,
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[sqlx::test]
+async fn failed_policy_writes_roll_back_the_new_user(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    sqlx::raw_sql(
+        r#"
+        CREATE FUNCTION fail_policy_write() RETURNS trigger AS $$
+        BEGIN
+            RAISE EXCEPTION 'injected policy write failure';
+        END
+        $$ LANGUAGE plpgsql;
+        CREATE TRIGGER fail_policy_write BEFORE INSERT ON casbin_rule
+        FOR EACH ROW WHEN (NEW.v0 = 'mallory') EXECUTE FUNCTION fail_policy_write();
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let req = create_user_request("mallory").unwrap();
+    assert!(BlogRepository::create_user(&pg, &req).await.is_err());
+    assert_eq!(common::user_count(&pool, "mallory").await, 0);
+    assert_eq!(common::casbin_rule_count(&pool, "mallory").await, 0);
+    let allowed = BlogRepository::check_permission(&pg, "mallory", "/api/users/mallory", "GET")
+        .await
+        .unwrap();
+    assert!(!allowed);
+}
+
+#[sqlx::test]
+async fn failed_deletes_keep_the_user_and_their_policy(pool: PgPool) {
+    let pg = common::pg(pool.clone()).await;
+    common::create_user(&pg, "alice", Role::Author).await;
+    let rules = common::casbin_rule_count(&pool, "alice").await;
+    assert!(rules > 0);
+    // Fails the commit, after the rules were deleted inside the transaction.
+    sqlx::raw_sql(
+        r#"
+        CREATE FUNCTION fail_commit() RETURNS trigger AS $$
+        BEGIN
+            RAISE EXCEPTION 'injected commit failure';
+        END
+        $$ LANGUAGE plpgsql;
+        CREATE CONSTRAINT TRIGGER fail_commit AFTER DELETE ON users
+        DEFERRABLE INITIALLY DEFERRED
+        FOR EACH ROW EXECUTE FUNCTION fail_commit();
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let req = DeleteUserRequest::new("alice".to_string()).unwrap();
+    assert!(BlogRepository::delete_user(&pg, &req).await.is_err());
+    assert_eq!(common::user_count(&pool, "alice").await, 1);
+    assert_eq!(common::casbin_rule_count(&pool, "alice").await, rules);
+    let allowed = BlogRepository::check_permission(&pg, "alice", "/api/users/alice", "GET")
+        .await
+        .unwrap();
+    assert!(allowed);
+}